@@ -0,0 +1,288 @@
+use crate::{ Command, SubCommand, Options };
+use crate::config::Config;
+use crate::converters::Application;
+use crate::errors::{ ParseError, report_error };
+use crate::parser::{ SegmentWrapper, Segment, TerminatorKind };
+use crate::spec::{ check_names, parse_arguments, parse_option, parse_sub_command };
+use crate::traits::{ GetArgs, GetOpts, IntoExitCode, PushArgument, PushOptions, PushSubCommand };
+use std::collections::HashMap;
+use std::ffi::OsString;
+
+type Action = Box<dyn Fn(&Application) -> i32>;
+
+/// Build a cli app at runtime (in the style of commander.js) instead of using macros, e.g.,
+///
+/// ```ignore
+/// Program::new("npms")
+///     .version("0.1.0")
+///     .option("-r, --registry <url>", "registry to use")
+///     .command("install|i <pkg>")
+///     .description("install a package")
+///     .option("-g, --global", "install globally")
+///     .action(|app| println!("install {:?}", app.get_sub_arg("pkg")))
+///     .run();
+/// ```
+///
+/// `option`, `arguments`, `alias`, `description` and `action` apply to the sub-command defined last by `command`,
+/// or to the command if no sub-command is defined yet.
+/// Specs are the same as the ones of macros (see `spec`), methods panic if a spec is malformed.
+pub struct Program {
+    cmd: Command,
+    // actions keyed by the path of sub-command, e.g., `remote add`, the action of command is keyed by ``
+    actions: HashMap<String, Action>,
+    // path of the sub-command defined last, it's empty if no sub-command is defined
+    current: String,
+}
+
+// names of sub-commands and aliases, e.g., `install`
+fn is_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn push_arguments<T: GetArgs + PushArgument>(target: &mut T, spec: &str) {
+    for arg in parse_arguments(spec).unwrap_or_else(|err| panic!("{}", err)) {
+        let len = target.get_args().len();
+        let name = arg.name.clone();
+
+        target.push_argument(arg);
+
+        // arguments which are duplicated or in a wrong order are not pushed
+        if target.get_args().len() == len {
+            panic!("invalid argument `{}`, it's duplicated or in a wrong order", name);
+        }
+    }
+}
+
+fn push_option<T: GetOpts + PushOptions>(target: &mut T, options: Options) {
+    let len = target.get_opts().len();
+    let long = options.long.clone();
+
+    target.push_option(options);
+
+    // options whose short or long names are duplicated are not pushed
+    if target.get_opts().len() == len {
+        panic!("invalid option `--{}`, it's duplicated", long);
+    }
+}
+
+impl Program {
+    /// The option `-h, --help` is defined by default.
+    pub fn new(name: &str) -> Self {
+        let mut cmd = Command::new(name.to_string(), None);
+
+        cmd.push_option(Options::new(
+            Some(String::from("h")),
+            String::from("help"),
+            Some(String::from("print help information")),
+        ));
+
+        Program { cmd, actions: HashMap::new(), current: String::new() }
+    }
+
+    /// Set the version of command and define the option `-v, --version`.
+    pub fn version(mut self, version: &str) -> Self {
+        self.cmd.version = version.to_string();
+        self.cmd.push_option(Options::new(
+            Some(String::from("v")),
+            String::from("version"),
+            Some(String::from("print version information")),
+        ));
+
+        self
+    }
+
+    pub fn description(mut self, desc: &str) -> Self {
+        match self.current_sub() {
+            Some(sub_cmd) => sub_cmd.desc = Some(desc.to_string()),
+            None => self.cmd.desc = Some(desc.to_string()),
+        }
+
+        self
+    }
+
+    /// Define an option, e.g., `-g, --global`, `-r, --registry <url>` or `--[no-]color`.
+    /// `desc` overrides the description in `spec` if it isn't empty.
+    /// It panics if the short name or the long name is already defined (`-h, --help` is defined by default).
+    pub fn option(mut self, spec: &str, desc: &str) -> Self {
+        let mut options = parse_option(spec).unwrap_or_else(|err| panic!("{}", err));
+
+        if !desc.is_empty() {
+            options.desc = Some(desc.to_string());
+        }
+
+        match self.current_sub() {
+            Some(sub_cmd) => push_option(sub_cmd, options),
+            None => push_option(&mut self.cmd, options),
+        }
+
+        self
+    }
+
+    /// Define arguments separated by spaces, e.g., `<src> [..files]`.
+    pub fn arguments(mut self, spec: &str) -> Self {
+        match self.current_sub() {
+            Some(sub_cmd) => push_arguments(sub_cmd, spec),
+            None => push_arguments(&mut self.cmd, spec),
+        }
+
+        self
+    }
+
+    /// Define a sub-command with its aliases and arguments, e.g., `install|i <pkg>` or `install <pkg>, "install a package"`.
+    /// Nested sub-command is defined by its path, e.g., `remote add <name> <url>`, its parent should be defined before.
+    pub fn command(mut self, spec: &str) -> Self {
+        let mut parents = vec![];
+        let mut rest = spec.trim_start();
+
+        // names followed by another name are parents, e.g., `remote` of `remote add`
+        while let Some((name, next)) = rest.split_once(char::is_whitespace) {
+            let next = next.trim_start();
+
+            if !is_name(name) || !next.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+                break;
+            }
+
+            parents.push(name);
+            rest = next;
+        }
+
+        let mut sub_cmd = parse_sub_command(rest).unwrap_or_else(|err| panic!("{}", err));
+        let parent_path = parents.join(" ");
+        let name = sub_cmd.name.clone();
+
+        sub_cmd.belong = if parents.is_empty() { self.cmd.name.clone() } else { format!("{} {}", self.cmd.name, parent_path) };
+
+        if parents.is_empty() {
+            self.cmd.push_sub_command(sub_cmd);
+            self.current = name;
+        } else {
+            match self.cmd.find_sub_cmd_mut(&parent_path) {
+                Some(parent) => parent.push_sub_command(sub_cmd),
+                None => panic!("unknown parent `{}` of sub-command `{}`", parent_path, spec),
+            }
+
+            self.current = format!("{} {}", parent_path, name);
+        }
+
+        self
+    }
+
+    /// Add an alias to the sub-command, e.g., `i` of `install`.
+    pub fn alias(mut self, alias: &str) -> Self {
+        match self.current_sub() {
+            Some(sub_cmd) if is_name(alias) => sub_cmd.aliases.push(alias.to_string()),
+            Some(_) => panic!("invalid alias `{}`", alias),
+            None => panic!("alias `{}` should follow a sub-command", alias),
+        }
+
+        self
+    }
+
+    /// Set the action called if the command (or the sub-command) is offered.
+    /// Like functions of macros, it returns any type which implements `IntoExitCode`.
+    pub fn action<F, R>(mut self, action: F) -> Self
+        where F: Fn(&Application) -> R + 'static, R: IntoExitCode {
+        self.actions.insert(self.current.clone(), Box::new(move |app| action(app).into_exit_code()));
+
+        self
+    }
+
+    /// Read values of options which are not offered from the config file, e.g., `config_file("config", "~/.npms.toml")`.
+    /// The path is the argument of the option `--<option>` if it's offered, or `default_path` otherwise.
+    /// Either of them can be empty. Keys of the file are long names of options, tables are sub-commands (see `config`).
+    /// It applies to the command even if sub-commands are defined.
+    pub fn config_file(mut self, option: &str, default_path: &str) -> Self {
+        let non_empty = |s: &str| if s.is_empty() { None } else { Some(s.to_string()) };
+
+        self.cmd.config = Some(Config { option: non_empty(option), path: non_empty(default_path) });
+
+        self
+    }
+
+    /// Expand `@file` in argv into arguments read from the file (see `response`).
+    /// It applies to the command even if sub-commands are defined.
+    pub fn response_files(mut self) -> Self {
+        self.cmd.response_files = true;
+
+        self
+    }
+
+    pub fn as_command(&self) -> &Command {
+        &self.cmd
+    }
+
+    /// Parse `std::env::args_os()` and call actions, the process exits with the exit code if it isn't `0`.
+    pub fn run(&self) {
+        let code = self.run_from(std::env::args_os());
+
+        if code != 0 {
+            std::process::exit(code);
+        }
+    }
+
+    /// Like `run`, but parse the specified argv and return the exit code instead of exiting, it's the same as `execute!`.
+    /// Note, the first element of `args` should be the path of cli, it will be ignored.
+    /// It panics if constraints of options refer to names which are not defined (see `spec::check_names`).
+    pub fn run_from<I, T>(&self, args: I) -> i32
+        where I: IntoIterator<Item = T>, T: Into<OsString> {
+        check_names(&self.cmd).unwrap_or_else(|err| panic!("{}", err));
+
+        let mut argv: Vec<OsString> = args.into_iter().map(Into::into).collect();
+        // errors are located in argv expanded from response files
+        let (parser_result, sources) = SegmentWrapper::parse_argv(&self.cmd, &mut argv);
+        let result = match &parser_result {
+            Ok(((cmd, sub_cmd), _, _)) => match Application::from_parser_result(&parser_result, &self.cmd) {
+                Ok(app) => {
+                    let app = app.with_sources(sources);
+                    let mut code = 0;
+
+                    // command is called if no sub-command is offered or arguments of command are offered
+                    if sub_cmd.is_none() || matches!(cmd, Some(Segment::Command(_, _, _))) {
+                        code = self.call("", &app);
+                    }
+
+                    // sub-command isn't called if command failed
+                    if let (0, Some(Segment::Command(Some(sub_path), _, _))) = (code, sub_cmd) {
+                        code = self.call(sub_path, &app);
+                    }
+
+                    Ok(code)
+                }
+                Err(_) => Err(ParseError::Internal(format!("{} {}", file!(), line!()))),
+            },
+            Err(TerminatorKind::GlobalHelp) => {
+                self.cmd.println();
+                Ok(0)
+            }
+            Err(TerminatorKind::GlobalVersion) | Err(TerminatorKind::Version(_)) => {
+                self.cmd.println_version();
+                Ok(0)
+            }
+            Err(TerminatorKind::Help(sub_path)) => {
+                self.cmd.println_sub(sub_path);
+                Ok(0)
+            }
+            Err(TerminatorKind::Error(err)) => Err(err.clone()),
+        };
+
+        match result {
+            Ok(code) => code,
+            Err(err) => {
+                report_error(&err.locate(&argv));
+                1
+            }
+        }
+    }
+
+    fn call(&self, path: &str, app: &Application) -> i32 {
+        self.actions.get(path).map_or(0, |action| action(app))
+    }
+
+    fn current_sub(&mut self) -> Option<&mut SubCommand> {
+        if self.current.is_empty() {
+            None
+        } else {
+            self.cmd.find_sub_cmd_mut(&self.current)
+        }
+    }
+}
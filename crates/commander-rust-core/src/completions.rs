@@ -0,0 +1,379 @@
+use crate::{ Command, SubCommand, Options, Argument, ArgumentType };
+use crate::converters::{ Application, Mixed };
+use crate::errors::ParseError;
+use crate::traits::{ PushArgument, PushOptions };
+use std::fmt;
+use std::str::FromStr;
+
+/// Name of the built-in sub-command which prints completion scripts.
+pub const SUB_COMMAND_NAME: &str = "completions";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub fn all() -> Vec<Shell> {
+        vec![Shell::Bash, Shell::Zsh, Shell::Fish]
+    }
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            _ => Err(format!("unsupported shell `{}`", s)),
+        }
+    }
+}
+
+// candidates of completion after the specified path of sub-commands has been inputted
+struct Node<'a> {
+    // names of sub-commands separated by spaces, it's empty for command
+    path: String,
+    sub_cmds: Vec<&'a SubCommand>,
+    // local options first, then global options
+    options: Vec<&'a Options>,
+}
+
+fn collect_nodes<'a>(cmd: &'a Command) -> Vec<Node<'a>> {
+    let mut nodes = vec![Node {
+        path: String::new(),
+        sub_cmds: cmd.sub_cmds.iter().collect(),
+        options: cmd.options.iter().collect(),
+    }];
+
+    for sub_cmd in cmd.sub_cmds.iter() {
+        collect_sub_nodes(cmd, sub_cmd, String::new(), &mut nodes);
+    }
+
+    nodes
+}
+
+fn collect_sub_nodes<'a>(cmd: &'a Command, sub_cmd: &'a SubCommand, prefix: String, nodes: &mut Vec<Node<'a>>) {
+    let path = if prefix.is_empty() { sub_cmd.name.clone() } else { format!("{} {}", prefix, sub_cmd.name) };
+    let mut options: Vec<&Options> = sub_cmd.options.iter().collect();
+
+    // global options are accepted by all sub-commands
+    for opt in cmd.options.iter() {
+        if !options.contains(&opt) {
+            options.push(opt);
+        }
+    }
+
+    nodes.push(Node {
+        path: path.clone(),
+        sub_cmds: sub_cmd.sub_cmds.iter().collect(),
+        options,
+    });
+
+    for nested in sub_cmd.sub_cmds.iter() {
+        collect_sub_nodes(cmd, nested, path.clone(), nodes);
+    }
+}
+
+// (current path, word inputted) => next path, aliases of sub-commands lead to the same path as their names
+fn transitions(nodes: &[Node]) -> Vec<(String, String, String)> {
+    let mut result = vec![];
+
+    for node in nodes.iter() {
+        for sub_cmd in node.sub_cmds.iter() {
+            let next = if node.path.is_empty() {
+                sub_cmd.name.clone()
+            } else {
+                format!("{} {}", node.path, sub_cmd.name)
+            };
+
+            for word in std::iter::once(&sub_cmd.name).chain(sub_cmd.aliases.iter()) {
+                result.push((node.path.clone(), word.clone(), next.clone()));
+            }
+        }
+    }
+
+    result
+}
+
+fn option_words(opt: &Options) -> Vec<String> {
+    let mut words = vec![format!("--{}", opt.long)];
+
+    if opt.negatable {
+        words.push(format!("--no-{}", opt.long));
+    }
+
+    if let Some(short) = &opt.short {
+        words.push(format!("-{}", short));
+    }
+
+    words
+}
+
+fn function_name(cmd: &Command) -> String {
+    cmd.name.chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect()
+}
+
+fn escape_single_quote(s: &str) -> String {
+    s.replace('\'', r"'\''")
+}
+
+fn generate_bash(cmd: &Command) -> String {
+    let nodes = collect_nodes(cmd);
+    let fn_name = format!("_{}", function_name(cmd));
+    let mut script = String::new();
+
+    script.push_str(&format!("{}() {{\n", fn_name));
+    script.push_str("    local cur prev sub_path i\n");
+    script.push_str("    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    script.push_str("    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+    script.push_str("    sub_path=\"\"\n\n");
+    script.push_str("    for ((i = 1; i < COMP_CWORD; i++)); do\n");
+    script.push_str("        case \"${sub_path}:${COMP_WORDS[i]}\" in\n");
+
+    for (from, word, to) in transitions(&nodes) {
+        script.push_str(&format!("            \"{}:{}\") sub_path=\"{}\" ;;\n", from, word, to));
+    }
+
+    script.push_str("        esac\n");
+    script.push_str("    done\n\n");
+    script.push_str("    case \"${sub_path}\" in\n");
+
+    for node in nodes.iter() {
+        let mut words: Vec<String> = node.sub_cmds.iter().map(|sub_cmd| sub_cmd.name.clone()).collect();
+        let mut words_with_args = vec![];
+
+        for opt in node.options.iter() {
+            if !opt.opt_args.is_empty() {
+                words_with_args.append(&mut option_words(opt));
+            }
+
+            words.append(&mut option_words(opt));
+        }
+
+        script.push_str(&format!("        \"{}\")\n", node.path));
+
+        // arguments of options are completed as files
+        if !words_with_args.is_empty() {
+            script.push_str(&format!("            case \"${{prev}}\" in\n                {})\n", words_with_args.join("|")));
+            script.push_str("                    COMPREPLY=( $(compgen -f -- \"${cur}\") )\n");
+            script.push_str("                    return 0\n");
+            script.push_str("                    ;;\n            esac\n");
+        }
+
+        script.push_str(&format!("            COMPREPLY=( $(compgen -W \"{}\" -- \"${{cur}}\") )\n", words.join(" ")));
+        script.push_str("            ;;\n");
+    }
+
+    script.push_str("    esac\n");
+    script.push_str("}\n\n");
+    script.push_str(&format!("complete -F {} -o bashdefault -o default {}\n", fn_name, cmd.name));
+
+    script
+}
+
+fn generate_zsh(cmd: &Command) -> String {
+    let nodes = collect_nodes(cmd);
+    let fn_name = format!("_{}", function_name(cmd));
+    let mut script = String::new();
+
+    script.push_str(&format!("#compdef {}\n\n", cmd.name));
+    script.push_str(&format!("{}() {{\n", fn_name));
+    script.push_str("    local sub_path=\"\" i\n");
+    script.push_str("    local -a candidates\n\n");
+    script.push_str("    for ((i = 2; i < CURRENT; i++)); do\n");
+    script.push_str("        case \"${sub_path}:${words[i]}\" in\n");
+
+    for (from, word, to) in transitions(&nodes) {
+        script.push_str(&format!("            \"{}:{}\") sub_path=\"{}\" ;;\n", from, word, to));
+    }
+
+    script.push_str("        esac\n");
+    script.push_str("    done\n\n");
+    script.push_str("    case \"${sub_path}\" in\n");
+
+    for node in nodes.iter() {
+        let mut candidates = vec![];
+        let mut words_with_args = vec![];
+
+        for sub_cmd in node.sub_cmds.iter() {
+            let desc = sub_cmd.desc.clone().unwrap_or_default();
+
+            candidates.push(format!("'{}:{}'", sub_cmd.name, escape_single_quote(&desc)));
+        }
+
+        for opt in node.options.iter() {
+            let desc = opt.desc.clone().unwrap_or_default();
+
+            if !opt.opt_args.is_empty() {
+                words_with_args.append(&mut option_words(opt));
+            }
+
+            for word in option_words(opt) {
+                candidates.push(format!("'{}:{}'", word, escape_single_quote(&desc)));
+            }
+        }
+
+        script.push_str(&format!("        \"{}\")\n", node.path));
+
+        // arguments of options are completed as files
+        if !words_with_args.is_empty() {
+            script.push_str(&format!("            case \"${{words[CURRENT-1]}}\" in\n                {})\n", words_with_args.join("|")));
+            script.push_str("                    _files\n");
+            script.push_str("                    return\n");
+            script.push_str("                    ;;\n            esac\n");
+        }
+
+        script.push_str("            candidates=(\n");
+
+        for candidate in candidates.iter() {
+            script.push_str(&format!("                {}\n", candidate));
+        }
+
+        script.push_str("            )\n");
+        script.push_str("            ;;\n");
+    }
+
+    script.push_str("    esac\n\n");
+    script.push_str("    _describe 'command' candidates\n");
+    script.push_str("}\n\n");
+    script.push_str(&format!("{} \"$@\"\n", fn_name));
+
+    script
+}
+
+fn generate_fish(cmd: &Command) -> String {
+    let nodes = collect_nodes(cmd);
+    let fn_name = format!("__fish_{}_using_path", function_name(cmd));
+    let mut script = String::new();
+
+    // check whether the path of sub-commands inputted is the specified one
+    script.push_str(&format!("function {}\n", fn_name));
+    script.push_str("    set -l sub_path \"\"\n");
+    script.push_str("    set -l words (commandline -opc)\n");
+    script.push_str("    set -e words[1]\n\n");
+    script.push_str("    for word in $words\n");
+    script.push_str("        switch \"$sub_path:$word\"\n");
+
+    for (from, word, to) in transitions(&nodes) {
+        script.push_str(&format!("            case \"{}:{}\"\n                set sub_path \"{}\"\n", from, word, to));
+    }
+
+    script.push_str("        end\n");
+    script.push_str("    end\n\n");
+    script.push_str("    test \"$sub_path\" = \"$argv[1]\"\n");
+    script.push_str("end\n\n");
+    script.push_str(&format!("complete -c {} -f\n", cmd.name));
+
+    for node in nodes.iter() {
+        let condition = format!("-n '{} \"{}\"'", fn_name, node.path);
+
+        for sub_cmd in node.sub_cmds.iter() {
+            let desc = sub_cmd.desc.clone().unwrap_or_default();
+
+            script.push_str(&format!(
+                "complete -c {} {} -a {} -d '{}'\n",
+                cmd.name, condition, sub_cmd.name, escape_fish(&desc),
+            ));
+        }
+
+        for opt in node.options.iter() {
+            let desc = opt.desc.clone().unwrap_or_default();
+            let short = if let Some(short) = &opt.short { format!(" -s {}", short) } else { String::new() };
+            // arguments of options are completed as files
+            let args = if opt.opt_args.is_empty() { "" } else { " -r -F" };
+
+            script.push_str(&format!(
+                "complete -c {} {}{} -l {}{} -d '{}'\n",
+                cmd.name, condition, short, opt.long, args, escape_fish(&desc),
+            ));
+
+            if opt.negatable {
+                script.push_str(&format!(
+                    "complete -c {} {} -l no-{} -d '{}'\n",
+                    cmd.name, condition, opt.long, escape_fish(&desc),
+                ));
+            }
+        }
+    }
+
+    script
+}
+
+fn escape_fish(s: &str) -> String {
+    s.replace('\\', r"\\").replace('\'', r"\'")
+}
+
+/// Generate the completion script of `cmd` for the specified shell.
+/// The script completes names of sub-commands (including nested sub-commands), long options and short options.
+/// If an option accepts arguments, its arguments are completed as file names.
+pub fn generate(cmd: &Command, shell: Shell) -> String {
+    match shell {
+        Shell::Bash => generate_bash(cmd),
+        Shell::Zsh => generate_zsh(cmd),
+        Shell::Fish => generate_fish(cmd),
+    }
+}
+
+fn shells() -> Vec<String> {
+    Shell::all().iter().map(|shell| shell.to_string()).collect()
+}
+
+/// The built-in sub-command `completions <shell>` which is pushed by `execute!` if `#[completions]` is used.
+pub fn sub_command(belong: String) -> SubCommand {
+    let mut sub_cmd = SubCommand::new(
+        belong,
+        SUB_COMMAND_NAME.to_string(),
+        Some(String::from("print the completion script")),
+    );
+
+    // shown in help information, e.g., `<shell: bash|zsh|fish>`
+    sub_cmd.push_argument(Argument {
+        name: String::from("shell"),
+        ty: ArgumentType::RequiredSingle,
+        default: None,
+        possible_values: Some(shells()),
+    });
+    sub_cmd.push_option(Options::new(
+        Some(String::from("h")),
+        String::from("help"),
+        Some(String::from("print help information")),
+    ));
+
+    sub_cmd
+}
+
+/// Print the completion script for the shell offered to the built-in sub-command, it's called by `execute!`.
+pub fn print_from_app(app: &Application) -> Result<(), ParseError> {
+    if let Some(Mixed::Single(shell)) = app.get_sub_arg("shell") {
+        match Shell::from_str(&shell.to_string_lossy()) {
+            Ok(shell) => {
+                print!("{}", generate(&app.command, shell));
+                Ok(())
+            }
+            Err(_) => Err(ParseError::InvalidValue {
+                token: shell.to_string(),
+                possible_values: shells(),
+                index: None,
+            }),
+        }
+    } else {
+        Err(ParseError::Internal(format!("{} {}", file!(), line!())))
+    }
+}
@@ -0,0 +1,243 @@
+//! Config files which provide values of options not offered in argv.
+//!
+//! Keys of the top level are long names of options of command, tables named after sub-commands
+//! (nested tables for nested sub-commands) contain options of sub-commands, e.g.,
+//!
+//! ```toml
+//! registry = "https://example.com"
+//!
+//! [install]
+//! global = true
+//! ```
+//!
+//! Files are read as JSON if they end with `.json`, as TOML otherwise.
+//! Reading files requires the feature `config`.
+//! The precedence of values is: command line > environment variables > config file > default values.
+
+use crate::errors::ParseError;
+use crate::parser::{Segment, SegmentWrapper};
+use crate::traits::{GetArgs, GetOpts, GetSubCmds};
+use crate::{Command, Options};
+use std::path::{Path, PathBuf};
+
+/// Where to find the config file of command.
+/// `option` is the long name of the option whose argument is the path, e.g., `config` of `--config <path>`.
+/// `path` is the default location used if the option isn't offered, a leading `~` means the home directory.
+/// The default location may not exist, but the path offered by the option must exist.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct Config {
+    pub option: Option<String>,
+    pub path: Option<String>,
+}
+
+/// Values of config files, numbers and dates are kept as strings.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigValue {
+    Bool(bool),
+    Str(String),
+    Array(Vec<ConfigValue>),
+    /// Keys are sorted rather than kept in the order of the file.
+    Table(Vec<(String, ConfigValue)>),
+}
+
+impl ConfigValue {
+    pub fn get(&self, key: &str) -> Option<&ConfigValue> {
+        match self {
+            ConfigValue::Table(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+fn invalid<T: Into<String>>(path: &Path, key: &str, message: T) -> ParseError {
+    ParseError::InvalidConfig {
+        token: path.to_string_lossy().to_string(),
+        key: key.to_string(),
+        message: message.into(),
+        index: None,
+    }
+}
+
+#[cfg(feature = "config")]
+fn from_toml(value: toml::Value) -> ConfigValue {
+    match value {
+        toml::Value::Boolean(b) => ConfigValue::Bool(b),
+        toml::Value::String(s) => ConfigValue::Str(s),
+        toml::Value::Array(items) => ConfigValue::Array(items.into_iter().map(from_toml).collect()),
+        toml::Value::Table(table) => ConfigValue::Table(table.into_iter().map(|(k, v)| (k, from_toml(v))).collect()),
+        other => ConfigValue::Str(other.to_string()),
+    }
+}
+
+#[cfg(feature = "config")]
+fn from_json(value: serde_json::Value) -> ConfigValue {
+    match value {
+        serde_json::Value::Bool(b) => ConfigValue::Bool(b),
+        serde_json::Value::String(s) => ConfigValue::Str(s),
+        serde_json::Value::Array(items) => ConfigValue::Array(items.into_iter().map(from_json).collect()),
+        // null is regarded as absent
+        serde_json::Value::Object(map) => ConfigValue::Table(
+            map.into_iter().filter(|(_, v)| !v.is_null()).map(|(k, v)| (k, from_json(v))).collect()
+        ),
+        other => ConfigValue::Str(other.to_string()),
+    }
+}
+
+#[cfg(feature = "config")]
+fn parse_content(path: &Path, content: &str) -> Result<ConfigValue, ParseError> {
+    let is_json = path.extension().is_some_and(|ext| ext == "json");
+    let value = if is_json {
+        serde_json::from_str(content).map(from_json).map_err(|err| invalid(path, "", err.to_string()))?
+    } else {
+        content.parse().map(from_toml).map_err(|err| invalid(path, "", err.to_string()))?
+    };
+
+    match value {
+        ConfigValue::Table(_) => Ok(value),
+        _ => Err(invalid(path, "", "the top level should be a table")),
+    }
+}
+
+#[cfg(not(feature = "config"))]
+fn parse_content(path: &Path, _content: &str) -> Result<ConfigValue, ParseError> {
+    Err(invalid(path, "", "the feature `config` is required to read config files"))
+}
+
+/// Read the config file, it's always a table.
+pub fn load(path: &Path) -> Result<ConfigValue, ParseError> {
+    let content = std::fs::read_to_string(path).map_err(|err| invalid(path, "", format!("can't read it, {}", err)))?;
+
+    parse_content(path, &content)
+}
+
+// expand the leading `~` of the default location
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Find and read the config file of command, `offered` are global options offered (or read from environment variables).
+/// Return `None` if the option isn't offered and the default location doesn't exist.
+/// The position is the one of the path in argv, it's `None` if the path isn't inputted, errors are located by it.
+pub fn load_for(cmd: &Command, offered: &[Segment]) -> Result<Option<(PathBuf, ConfigValue, Option<usize>)>, ParseError> {
+    let config = match &cmd.config {
+        Some(config) => config,
+        None => return Ok(None),
+    };
+    let def = config.option.as_ref().and_then(|long| cmd.get_opts().iter().find(|opt| &opt.long == long));
+    // the last occurrence wins
+    let offered_path = def.and_then(|def| {
+        offered.iter().rev().find_map(|seg| match seg {
+            Segment::Long(name, args, _) if name == &def.long => args.first(),
+            Segment::Short(name, args, _) if Some(name) == def.short.as_ref() => args.first(),
+            _ => None,
+        })
+    });
+
+    if let Some(Segment::Raw(path, at)) = offered_path {
+        let path = PathBuf::from(path);
+
+        return load(&path).map(|value| Some((path, value, *at))).map_err(|err| err.with_index(*at));
+    }
+
+    match &config.path {
+        Some(path) => {
+            let path = expand_home(path);
+
+            if path.exists() {
+                load(&path).map(|value| Some((path, value, None)))
+            } else {
+                Ok(None)
+            }
+        }
+        None => Ok(None),
+    }
+}
+
+// keys are long names, `_` is regarded as `-`, e.g., `dry_run` of `--dry-run`
+fn find_option<'a>(options: &'a [Options], key: &str) -> Option<&'a Options> {
+    options.iter().find(|opt| opt.long == key || opt.long == key.replace('_', "-"))
+}
+
+// convert the value to the segment of option, `None` if it means that the option isn't offered
+fn to_segment(path: &Path, opt: &Options, key: &str, value: &ConfigValue) -> Result<Option<Segment>, ParseError> {
+    let scalar = |value: &ConfigValue| match value {
+        ConfigValue::Bool(b) => Ok(Segment::Raw(b.to_string().into(), None)),
+        ConfigValue::Str(s) => Ok(Segment::Raw(s.into(), None)),
+        _ => Err(invalid(path, key, format!("`{}` expects a value or an array of values", key))),
+    };
+
+    if opt.get_args().is_empty() {
+        return match value {
+            ConfigValue::Bool(true) => Ok(Some(Segment::Long(opt.long.clone(), vec![], None))),
+            ConfigValue::Bool(false) if opt.negatable => Ok(Some(Segment::Long(format!("no-{}", opt.long), vec![], None))),
+            ConfigValue::Bool(false) => Ok(None),
+            _ => Err(invalid(path, key, format!("`{}` expects a boolean", key))),
+        };
+    }
+
+    let args = match value {
+        ConfigValue::Array(items) if items.is_empty() => return Ok(None),
+        ConfigValue::Array(items) => items.iter().map(scalar).collect::<Result<Vec<Segment>, ParseError>>()?,
+        _ => vec![scalar(value)?],
+    };
+    let seg = Segment::Long(opt.long.clone(), args, None);
+
+    if let Err(ParseError::BadOptionArgument { expected, .. }) = SegmentWrapper::check_arguments(&seg, opt, "") {
+        return Err(invalid(path, key, format!("arguments of `{}` are defined as `{}`", key, expected)));
+    }
+
+    Ok(Some(seg))
+}
+
+/// Check that every key of `table` is an option or a sub-command of `def` and values of options are valid,
+/// `prefix` is the dotted path of `table`.
+pub(crate) fn validate<T: GetOpts + GetSubCmds>(path: &Path, def: &T, table: &ConfigValue, prefix: &str) -> Result<(), ParseError> {
+    if let ConfigValue::Table(entries) = table {
+        for (key, value) in entries {
+            let full_key = if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) };
+
+            if let (Some(sub_cmd), ConfigValue::Table(_)) = (def.get_sub_cmds().iter().find(|sub| &sub.name == key), value) {
+                validate(path, sub_cmd, value, &full_key)?;
+            } else if let Some(opt) = find_option(def.get_opts(), key) {
+                to_segment(path, opt, &full_key, value)?;
+            } else {
+                return Err(invalid(path, &full_key, format!("unknown key `{}`", full_key)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Options of `def` which are in `table` but not `offered`, with their long names and dotted keys, e.g., `install.global`.
+/// `table` should be validated.
+pub(crate) fn options<T: GetOpts>(
+    path: &Path,
+    def: &T,
+    table: &ConfigValue,
+    prefix: &str,
+    offered: &[Segment],
+) -> Result<Vec<(Segment, String, String)>, ParseError> {
+    let mut options = vec![];
+
+    if let ConfigValue::Table(entries) = table {
+        for (key, value) in entries {
+            let opt = match (find_option(def.get_opts(), key), value) {
+                // tables of sub-commands are skipped
+                (_, ConfigValue::Table(_)) => continue,
+                (Some(opt), _) if !opt.is_offered(offered) => opt,
+                _ => continue,
+            };
+            let full_key = if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) };
+
+            if let Some(seg) = to_segment(path, opt, &full_key, value)? {
+                options.push((seg, opt.long.clone(), full_key));
+            }
+        }
+    }
+
+    Ok(options)
+}
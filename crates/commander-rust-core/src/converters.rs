@@ -1,36 +1,87 @@
-use crate::parser::{ Segment, ParserResult, };
-use crate::traits::{ GetArgs, GetOpt };
-use crate::Command;
+use crate::errors::ParseError;
+use crate::parser::{ Segment, ParserResult, OptionSources, Source };
+use crate::traits::{ GetArgs, GetOpts, GetOpt };
+use crate::{ Command, Options, RepeatPolicy };
 use std::ops::{ Deref, DerefMut };
+use std::marker::PhantomData;
 use std::str::FromStr;
 use std::num::ParseIntError;
 use std::path::{ PathBuf, Path };
 use std::fmt::Debug;
 use std::fmt;
-use std::collections::HashMap;
+use std::collections::{ HashMap, HashSet };
+use std::ffi::{ OsString, OsStr };
+
+static EMPTY_ARG: Arg = Arg(OsString::new());
+static EMPTY_ARGS: Args = Args(vec![]);
 
 /// type conversion needed
 pub trait FromArg<'a>: Sized {
     type Error: Debug;
     fn from_arg(arg: &'a Arg) -> Result<Self, Self::Error>;
+
+    /// Called if the argument is not offered and it has no default value, e.g., `[arg]` without input.
+    /// By default, it's converted from an empty argument.
+    fn from_absent() -> Result<Self, Self::Error> {
+        Self::from_arg(&EMPTY_ARG)
+    }
+
+    /// Values accepted if the type only accepts several values, e.g., `noodles`, `beef` and `fish`.
+    /// They are shown in help information, e.g., `<food: noodles|beef|fish>`.
+    /// By default, it's `None` which means any value could be accepted.
+    fn possible_values() -> Option<Vec<&'static str>> {
+        None
+    }
 }
 
 pub trait FromArgs<'a>: Sized {
     type Error: Debug;
     fn from_args(args: &'a Args) -> Result<Self, Self::Error>;
+
+    /// Called if the arguments are not offered and they have no default value, e.g., `[..args]` without input.
+    /// By default, it's converted from empty arguments.
+    fn from_absent() -> Result<Self, Self::Error> {
+        Self::from_args(&EMPTY_ARGS)
+    }
+
+    /// Values accepted by each argument, see `FromArg::possible_values`.
+    fn possible_values() -> Option<Vec<&'static str>> {
+        None
+    }
 }
 
-#[derive(Clone, Debug)]
-pub struct Arg(pub String);
+/// An argument offered, it may be invalid UTF-8 (e.g., file names on Unix).
+/// Convert it into `PathBuf` or `OsString` losslessly, converting invalid UTF-8 into `String` fails.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Arg(pub OsString);
 
+impl From<&str> for Arg {
+    fn from(s: &str) -> Self {
+        Arg(OsString::from(s))
+    }
+}
+
+impl From<String> for Arg {
+    fn from(s: String) -> Self {
+        Arg(OsString::from(s))
+    }
+}
+
+impl From<OsString> for Arg {
+    fn from(s: OsString) -> Self {
+        Arg(s)
+    }
+}
+
+/// Invalid UTF-8 is replaced with `U+FFFD`.
 impl fmt::Display for Arg {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.0.to_string_lossy())
     }
 }
 
 impl Deref for Arg {
-    type Target = String;
+    type Target = OsString;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -52,18 +103,38 @@ impl<'a> FromArg<'a> for &'a Arg {
     }
 }
 
+// the argument is invalid UTF-8
 impl<'a> FromArg<'a> for String {
     type Error = ();
 
     #[inline]
     fn from_arg(arg: &'a Arg) -> Result<String, Self::Error> {
-        Ok(arg.0.clone())
+        arg.0.to_str().map(String::from).ok_or(())
     }
 }
 
+// the argument is invalid UTF-8
 impl<'a> FromArg<'a> for &'a str {
     type Error = ();
 
+    #[inline]
+    fn from_arg(arg: &'a Arg) -> Result<Self, Self::Error> {
+        arg.0.to_str().ok_or(())
+    }
+}
+
+impl<'a> FromArg<'a> for OsString {
+    type Error = ();
+
+    #[inline]
+    fn from_arg(arg: &'a Arg) -> Result<Self, Self::Error> {
+        Ok(arg.0.clone())
+    }
+}
+
+impl<'a> FromArg<'a> for &'a OsStr {
+    type Error = ();
+
     #[inline]
     fn from_arg(arg: &'a Arg) -> Result<Self, Self::Error> {
         Ok(&arg.0)
@@ -74,7 +145,7 @@ impl<'a> FromArg<'a> for PathBuf {
     type Error = ();
 
     fn from_arg(arg: &'a Arg) -> Result<Self, Self::Error> {
-        Ok(PathBuf::from(arg.as_str()))
+        Ok(PathBuf::from(&arg.0))
     }
 }
 
@@ -82,7 +153,7 @@ impl<'a> FromArg<'a> for &'a Path {
     type Error = ();
 
     fn from_arg(arg: &'a Arg) -> Result<Self, Self::Error> {
-        Ok(Path::new(arg.as_str()))
+        Ok(Path::new(&arg.0))
     }
 }
 
@@ -94,7 +165,8 @@ macro_rules! impl_ints {
 
                 #[inline]
                 fn from_arg(arg: &'a Arg) -> Result<$T, Self::Error> {
-                    <$T as FromStr>::from_str(&arg)
+                    // invalid UTF-8 is replaced with `U+FFFD`, which isn't a digit
+                    <$T as FromStr>::from_str(&arg.to_string_lossy())
                 }
             }
         )*
@@ -114,6 +186,19 @@ impl<'a, T: FromArg<'a>> FromArg<'a> for Result<T, T::Error> {
             Err(e) => Ok(Err(e)),
         }
     }
+
+    #[inline]
+    fn from_absent() -> Result<Self, Self::Error> {
+        match T::from_absent() {
+            Ok(val) => Ok(Ok(val)),
+            Err(e) => Ok(Err(e)),
+        }
+    }
+
+    #[inline]
+    fn possible_values() -> Option<Vec<&'static str>> {
+        T::possible_values()
+    }
 }
 
 impl<'a, T: FromArg<'a>> FromArg<'a> for Option<T> {
@@ -126,6 +211,16 @@ impl<'a, T: FromArg<'a>> FromArg<'a> for Option<T> {
             Err(_) => Ok(None),
         }
     }
+
+    #[inline]
+    fn from_absent() -> Result<Self, Self::Error> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn possible_values() -> Option<Vec<&'static str>> {
+        T::possible_values()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -134,14 +229,14 @@ pub struct Args(pub Vec<Arg>);
 impl From<Segment> for Args {
     fn from(seg: Segment) -> Args {
         match seg {
-            Segment::Short(_, args)
-            | Segment::Long(_, args)
-            | Segment::Command(_, args) => {
+            Segment::Short(_, args, _)
+            | Segment::Long(_, args, _)
+            | Segment::Command(_, args, _) => {
                 let mut args_strs = vec![];
 
                 for arg in args {
-                    if let Segment::Raw(str) = arg {
-                        args_strs.push(Arg(str));
+                    if let Segment::Raw(s, _) = arg {
+                        args_strs.push(Arg(s));
                     }
                 }
 
@@ -178,11 +273,16 @@ impl DerefMut for Args {
     }
 }
 
+// one of arguments is invalid UTF-8
 impl<'a> FromArgs<'a> for String {
     type Error = ();
 
     fn from_args(args: &'a Args) -> Result<Self, Self::Error> {
-        Ok(format!("{}", args))
+        if args.iter().all(|arg| arg.to_str().is_some()) {
+            Ok(format!("{}", args))
+        } else {
+            Err(())
+        }
     }
 }
 
@@ -209,6 +309,10 @@ impl<'a, T: FromArg<'a>> FromArgs<'a> for Vec<T> {
 
         Ok(result)
     }
+
+    fn possible_values() -> Option<Vec<&'static str>> {
+        T::possible_values()
+    }
 }
 
 
@@ -222,6 +326,16 @@ impl<'a, T: FromArgs<'a>> FromArgs<'a> for Option<T> {
             Err(_) => Ok(None),
         }
     }
+
+    #[inline]
+    fn from_absent() -> Result<Self, Self::Error> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn possible_values() -> Option<Vec<&'static str>> {
+        T::possible_values()
+    }
 }
 
 impl<'a, T: FromArgs<'a>> FromArgs<'a> for Result<T, T::Error> {
@@ -233,6 +347,17 @@ impl<'a, T: FromArgs<'a>> FromArgs<'a> for Result<T, T::Error> {
             Err(e) => Ok(Err(e)),
         }
     }
+
+    fn from_absent() -> Result<Self, Self::Error> {
+        match T::from_absent() {
+            Ok(val) => Ok(Ok(val)),
+            Err(e) => Ok(Err(e)),
+        }
+    }
+
+    fn possible_values() -> Option<Vec<&'static str>> {
+        T::possible_values()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -243,112 +368,224 @@ pub enum Mixed {
 
 #[derive(Debug, Clone, Default)]
 pub struct Application {
+    // name of the sub-command offered, it's the last one if sub-commands are nested
     pub sub_name: Option<String>,
+    // names of sub-commands offered from the outermost to the innermost, e.g., `["remote", "add"]`
+    pub sub_path: Vec<String>,
     pub sub_args: HashMap<String, Mixed>,
     pub cmd_args: HashMap<String, Mixed>,
     pub local_opts: HashMap<String, HashMap<String, Mixed>>,
     pub global_opts: HashMap<String, HashMap<String, Mixed>>,
+    // arguments of every occurrence of options in order, keyed by long names
+    pub(crate) local_occurrences: HashMap<String, Vec<Args>>,
+    pub(crate) global_occurrences: HashMap<String, Vec<Args>>,
+    // long names of negatable flags whose last occurrence is the negation, e.g., `color` of `--no-color`
+    pub(crate) local_negated: HashSet<String>,
+    pub(crate) global_negated: HashSet<String>,
+    // sources of options which are not offered in argv, see `Application::get_opt_source`
+    pub(crate) sources: OptionSources,
     pub(crate) command: Command,
 }
 
+// the same option is offered again, merge it into the previous one according to `RepeatPolicy`
+fn merge_repeated(policy: RepeatPolicy, prev: &mut HashMap<String, Mixed>, curr: HashMap<String, Mixed>) {
+    match policy {
+        RepeatPolicy::LastWins | RepeatPolicy::Error => *prev = curr,
+        RepeatPolicy::FirstWins => {},
+        RepeatPolicy::Append => {
+            for (name, mixed) in curr {
+                let merged = match (prev.remove(&name), mixed) {
+                    (None, mixed) => mixed,
+                    (Some(Mixed::Single(a)), Mixed::Single(b)) => Mixed::Multiply(Args(vec![a, b])),
+                    (Some(Mixed::Single(a)), Mixed::Multiply(Args(b))) => Mixed::Multiply(Args(std::iter::once(a).chain(b).collect())),
+                    (Some(Mixed::Multiply(Args(mut a))), Mixed::Single(b)) => {
+                        a.push(b);
+                        Mixed::Multiply(Args(a))
+                    }
+                    (Some(Mixed::Multiply(Args(mut a))), Mixed::Multiply(Args(mut b))) => {
+                        a.append(&mut b);
+                        Mixed::Multiply(Args(a))
+                    }
+                };
+
+                prev.insert(name, merged);
+            }
+        }
+    }
+}
+
+// flatten arguments of an option in the order of definition
+fn flatten_opt_args(def: &Options, offered: &HashMap<String, Mixed>) -> Args {
+    let mut args = vec![];
+
+    for arg in def.get_args().iter() {
+        match offered.get(&arg.name) {
+            Some(Mixed::Single(arg)) => args.push(arg.clone()),
+            Some(Mixed::Multiply(multiply)) => args.extend(multiply.iter().cloned()),
+            None => {},
+        }
+    }
+
+    Args(args)
+}
+
 // alias it for using
 pub type App = Application;
 
+// arguments of options, keyed by short names and long names
+type MixedOpts = HashMap<String, HashMap<String, Mixed>>;
+
+// options extracted from segments, see `Application::extract_args_for_options`
+struct ExtractedOpts {
+    // arguments merged according to `RepeatPolicy`
+    opts: MixedOpts,
+    // arguments of every occurrence
+    occurrences: HashMap<String, Vec<Args>>,
+    negated: HashSet<String>,
+}
+
 impl Application {
     fn extract_args<T: GetArgs>(args: &[Segment], def: &T) -> HashMap<String, Mixed> {
         let mut cmd_args = HashMap::new();
 
         // Since the arguments have been validated when entering this step,
         // the input arguments must match the defined arguments
+        // (except options which are not offered but have default values, all of their arguments are not offered)
         for (idx, cmd_arg) in def.get_args().iter().enumerate() {
             if !cmd_arg.ty.is_multiply() {
-                if cmd_arg.ty.is_required() {
-                    // for <arg>
-                    if let Segment::Raw(s) = &args[idx] {
-                        cmd_args.insert(cmd_arg.name.clone(), Mixed::Single(Arg(s.to_string())));
-                    }
-                } else {
-                    // for [arg]
-                    if !args.is_empty() && idx < args.len() {
-                        // [arg] with input
-                        if let Segment::Raw(s) = &args[idx] {
-                            cmd_args.insert(cmd_arg.name.clone(), Mixed::Single(Arg(s.to_string())));
-                        }
-                    } else {
-                        // [arg] without input
-                        cmd_args.insert(cmd_arg.name.clone(), Mixed::Single(Arg(String::new())));
-                    }
+                // for <arg> or [arg]
+                if let Some(Segment::Raw(s, _)) = args.get(idx) {
+                    cmd_args.insert(cmd_arg.name.clone(), Mixed::Single(Arg(s.clone())));
+                } else if let Some(default) = &cmd_arg.default {
+                    cmd_args.insert(cmd_arg.name.clone(), Mixed::Single(Arg::from(default.as_str())));
                 }
+                // [arg] without input and default value is absent
             } else if idx < args.len() {
                 // for <..args> or [..args]
                 let mut mixed_args = vec![];
 
                 for arg in args.iter().skip(idx) {
-                    if let Segment::Raw(s) = arg {
-                        mixed_args.push(Arg(s.to_string()));
+                    if let Segment::Raw(s, _) = arg {
+                        mixed_args.push(Arg(s.clone()));
                     }
                 }
 
                 cmd_args.insert(cmd_arg.name.clone(), Mixed::Multiply(Args(mixed_args)));
+            } else if let Some(default) = &cmd_arg.default {
+                cmd_args.insert(cmd_arg.name.clone(), Mixed::Multiply(Args(vec![Arg::from(default.as_str())])));
             }
         }
 
         cmd_args
     }
 
-    fn extract_args_for_options<T: GetOpt>(opts: &[Segment], def: &T) -> HashMap<String, HashMap<String, Mixed>> {
-        let mut mixed_opts = HashMap::new();
+    fn extract_args_for_options<T: GetOpts>(opts: &[Segment], def: &T) -> ExtractedOpts {
+        let mut mixed_opts: MixedOpts = HashMap::new();
+        let mut occurrences: HashMap<String, Vec<Args>> = HashMap::new();
+        let mut negated = HashSet::new();
 
         for opt in opts.iter() {
-            match opt {
-                Segment::Long(name, args) => {
-                    if let Some(opt) = def.get_long_opt(name) {
-                        if let Some(short) = &opt.short {
-                            mixed_opts.insert(short.to_string(), Self::extract_args(args, opt));
-                        }
-                        mixed_opts.insert(opt.long.to_string(), Self::extract_args(args, opt));
-                    }
-                },
-                Segment::Short(name, args) => {
-                    if let Some(opt) = def.get_short_opt(name) {
-                        if let Some(short) = &opt.short {
-                            mixed_opts.insert(short.to_string(), Self::extract_args(args, opt));
-                        }
-                        mixed_opts.insert(opt.long.to_string(), Self::extract_args(args, opt));
-                    }
+            let (opt, args, is_negated) = match opt {
+                Segment::Long(name, args, _) => {
+                    let opt = def.get_long_opt(name);
+
+                    (opt, args, opt.is_some_and(|opt| opt.is_negated(name)))
+                }
+                Segment::Short(name, args, _) => (def.get_short_opt(name), args, false),
+                _ => continue,
+            };
+
+            if let (Some(opt), true) = (opt, is_negated) {
+                // the negation cancels previous occurrences, e.g., `--color --no-color`
+                if let Some(short) = &opt.short {
+                    mixed_opts.remove(short);
+                }
+                mixed_opts.remove(&opt.long);
+                occurrences.remove(&opt.long);
+                negated.insert(opt.long.to_string());
+            } else if let Some(opt) = opt {
+                let mut extracted = Self::extract_args(args, opt);
+
+                occurrences.entry(opt.long.to_string()).or_default().push(flatten_opt_args(opt, &extracted));
+
+                if let Some(prev) = mixed_opts.get(&opt.long) {
+                    let mut merged = prev.clone();
+
+                    merge_repeated(opt.repeat, &mut merged, extracted);
+                    extracted = merged;
                 }
-                _ => {}
+
+                if let Some(short) = &opt.short {
+                    mixed_opts.insert(short.to_string(), extracted.clone());
+                }
+                mixed_opts.insert(opt.long.to_string(), extracted);
+                negated.remove(&opt.long);
+            }
+        }
+
+        // if an option is not offered, but all of its required arguments have default values
+        // it's regarded as offered with default values
+        for opt in def.get_opts().iter() {
+            let args = opt.get_args();
+            let has_default = args.iter().any(|arg| arg.default.is_some());
+            let all_default = args.iter().all(|arg| !arg.ty.is_required() || arg.default.is_some());
+
+            if has_default && all_default && !mixed_opts.contains_key(&opt.long) {
+                let extracted = Self::extract_args(&[], opt);
+
+                if let Some(short) = &opt.short {
+                    mixed_opts.insert(short.to_string(), extracted.clone());
+                }
+                mixed_opts.insert(opt.long.to_string(), extracted);
             }
         }
 
-        mixed_opts
+        ExtractedOpts {
+            opts: mixed_opts,
+            occurrences,
+            negated,
+        }
     }
 
     pub fn from_parser_result(parser_result: &ParserResult, cmd: &Command) -> Result<Self, String> {
         if let Ok(((in_cmd, in_sub), in_local_opts, in_global_opts)) = parser_result {
             let mut sub_name = None;
+            let mut sub_path = vec![];
             let mut local_opts = HashMap::new();
-            let cmd_args = if let Some(Segment::Command(_, args)) = in_cmd {
+            let mut local_occurrences = HashMap::new();
+            let mut local_negated = HashSet::new();
+            let cmd_args = if let Some(Segment::Command(_, args, _)) = in_cmd {
                 Self::extract_args(args, cmd)
-            } else { HashMap::new() };
-            let sub_args = if let Some(Segment::Command(Some(name), args)) = in_sub {
-                sub_name = Some(name.to_string());
+            } else { Self::extract_args(&[], cmd) };
+            let sub_args = if let Some(Segment::Command(Some(name), args, _)) = in_sub {
+                sub_path = name.split(' ').map(String::from).collect();
+                sub_name = sub_path.last().cloned();
 
-                if let Some(sub_cmd) = cmd.get_sub_cmd(name) {
-                    local_opts = Self::extract_args_for_options(&in_local_opts, sub_cmd);
+                if let Some(sub_cmd) = cmd.find_sub_cmd(name) {
+                    let extracted = Self::extract_args_for_options(in_local_opts, sub_cmd);
+
+                    local_opts = extracted.opts;
+                    local_occurrences = extracted.occurrences;
+                    local_negated = extracted.negated;
                     Self::extract_args(args, sub_cmd)
                 } else {
                     return Err(format!("can not find `{}`?", name));
                 }
             } else { HashMap::new() };
-            let global_opts = Self::extract_args_for_options(in_global_opts, cmd);
+            let global = Self::extract_args_for_options(in_global_opts, cmd);
 
             return Ok(Application {
                 sub_name,
+                sub_path,
                 sub_args,
                 cmd_args,
                 local_opts,
-                global_opts,
+                global_opts: global.opts,
+                local_occurrences,
+                global_occurrences: global.occurrences,
+                local_negated,
+                global_negated: global.negated,
+                sources: OptionSources::default(),
                 command: cmd.clone(),
             });
         }
@@ -379,6 +616,94 @@ impl Application {
         }
     }
 
+    /// Arguments of the option offered in the order of definition, e.g., `--size <width> <height>`.
+    /// Local options are found firstly, then global options. Return `None` if the option is not offered.
+    /// If the option uses `RepeatPolicy::Append`, arguments of all occurrences are returned in the order of input.
+    /// It's used by `#[derive(FromApp)]`.
+    pub fn get_opt_args(&self, long: &str) -> Option<Args> {
+        let (offered, def) = match (self.local_opts.get(long), self.global_opts.get(long)) {
+            (Some(offered), _) => {
+                (offered, self.command.find_sub_cmd(&self.sub_path()).and_then(|sub_cmd| sub_cmd.get_long_opt(long)))
+            }
+            (None, Some(offered)) => (offered, self.command.get_long_opt(long)),
+            (None, None) => return None,
+        };
+
+        match def {
+            // arguments are accumulated argument by argument, so flatten occurrences to keep the order of input
+            Some(def) if def.repeat == RepeatPolicy::Append && self.get_opt_count(long) > 0 => {
+                Some(Args(self.get_opt_occurrences(long).into_iter().flat_map(|args| args.0).collect()))
+            }
+            Some(def) => Some(flatten_opt_args(def, offered)),
+            None => Some(Args(vec![])),
+        }
+    }
+
+    /// Arguments of every occurrence of the option in the order of input, e.g., `[[a], [b]]` for `-I a -I b`.
+    /// Unlike `get_opt_args`, it's not affected by `RepeatPolicy`.
+    /// Local options are found firstly, then global options. Return an empty `Vec` if the option is not offered
+    /// (default values of options not offered are not occurrences).
+    pub fn get_opt_occurrences(&self, long: &str) -> Vec<Args> {
+        self.local_occurrences
+            .get(long)
+            .or_else(|| self.global_occurrences.get(long))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Final value of the flag, e.g., `Some(false)` if the last one of `--color` and `--no-color` is `--no-color`.
+    /// Local options are found firstly, then global options. Return `None` if neither is offered.
+    pub fn get_flag(&self, long: &str) -> Option<bool> {
+        if self.local_opts.contains_key(long) {
+            Some(true)
+        } else if self.local_negated.contains(long) {
+            Some(false)
+        } else if self.global_opts.contains_key(long) {
+            Some(true)
+        } else if self.global_negated.contains(long) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Times the option is offered, e.g., `3` for `-vvv` if `-v` is the short name of `--verbose`.
+    pub fn get_opt_count(&self, long: &str) -> usize {
+        self.local_occurrences
+            .get(long)
+            .or_else(|| self.global_occurrences.get(long))
+            .map_or(0, Vec::len)
+    }
+
+    /// Attach sources of options returned by `SegmentWrapper::parse_with_sources`.
+    pub fn with_sources(mut self, sources: OptionSources) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// Where the final value of the option comes from, i.e., command line, environment variable, config file or default values.
+    /// Local options are found firstly, then global options. Return `None` if the option is not offered.
+    /// Note, options read from environment variables or config files are regarded as offered in command line
+    /// unless sources are attached (see `with_sources`), `execute!` and `Program` always attach them.
+    pub fn get_opt_source(&self, long: &str) -> Option<Source> {
+        let scopes = [
+            (&self.local_opts, &self.local_occurrences, &self.local_negated, &self.sources.local),
+            (&self.global_opts, &self.global_occurrences, &self.global_negated, &self.sources.global),
+        ];
+
+        for (opts, occurrences, negated, sources) in scopes.iter() {
+            if let Some(source) = sources.get(long) {
+                return Some(source.clone());
+            } else if occurrences.contains_key(long) || negated.contains(long) {
+                return Some(Source::Cli);
+            } else if opts.contains_key(long) {
+                return Some(Source::Default);
+            }
+        }
+
+        None
+    }
+
     pub fn get_sub_arg<T: ToString>(&self, key: T) -> Option<&Mixed> {
         self.sub_args.get(&key.to_string())
     }
@@ -391,6 +716,11 @@ impl Application {
         self.sub_name.clone().unwrap()
     }
 
+    /// Path of the sub-command offered, e.g., `remote add`.
+    pub fn sub_path(&self) -> String {
+        self.sub_path.join(" ")
+    }
+
     pub fn args_len(&self) -> usize {
         self.sub_args.len()
     }
@@ -403,6 +733,22 @@ impl Application {
 pub trait FromApp<'a>: Sized {
     type Error: Debug;
     fn from_app(app: &'a Application) -> Result<Self, Self::Error>;
+
+    /// Inputs of functions are converted by it, `long` is the name of the input in kebab-case,
+    /// i.e., the long name of the option with the same name (see `Count`). By default, `long` is ignored.
+    fn from_named(app: &'a Application, _long: &str) -> Result<Self, Self::Error> {
+        Self::from_app(app)
+    }
+
+    /// Called if inputs of functions fail to be converted, the error returned is reported, `ty` is the name of the type.
+    /// By default, it's `ParseError::ConversionFailed` whose token is `App`.
+    fn parse_error(_err: Self::Error, ty: &str) -> ParseError {
+        ParseError::ConversionFailed {
+            token: String::from("App"),
+            ty: ty.to_string(),
+            index: None,
+        }
+    }
 }
 
 impl<'a> FromApp<'a> for Application {
@@ -429,6 +775,33 @@ impl<'a> FromApp<'a> for &'a Command {
     }
 }
 
+/// Times an option is offered, e.g., `3` for `-vvv`.
+/// Inputs of functions with type `Count` receive the count of the option with the same name in kebab-case,
+/// e.g., `verbose: Count` for `--verbose`, so do fields of structs which derive `FromApp`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Count(pub usize);
+
+impl<'a> FromApp<'a> for Count {
+    type Error = ();
+
+    // it's always converted from the option with the same name
+    fn from_app(_app: &'a Application) -> Result<Self, Self::Error> {
+        Err(())
+    }
+
+    fn from_named(app: &'a Application, long: &str) -> Result<Self, Self::Error> {
+        Ok(Count(app.get_opt_count(long)))
+    }
+}
+
+impl Deref for Count {
+    type Target = usize;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 #[derive(Debug)]
 pub struct Opts(pub HashMap<String, HashMap<String, Mixed>>);
 
@@ -489,6 +862,14 @@ impl<'a, T: FromApp<'a>> FromApp<'a> for Result<T, T::Error> {
             Err(e) => Ok(Err(e)),
         }
     }
+
+    #[inline]
+    fn from_named(app: &'a App, long: &str) -> Result<Self, Self::Error> {
+        match T::from_named(app, long) {
+            Ok(val) => Ok(Ok(val)),
+            Err(e) => Ok(Err(e)),
+        }
+    }
 }
 
 impl<'a, T: FromApp<'a>> FromApp<'a> for Option<T> {
@@ -501,5 +882,61 @@ impl<'a, T: FromApp<'a>> FromApp<'a> for Option<T> {
             Err(_) => Ok(None),
         }
     }
+
+    #[inline]
+    fn from_named(app: &'a App, long: &str) -> Result<Self, Self::Error> {
+        match T::from_named(app, long) {
+            Ok(val) => Ok(Some(val)),
+            Err(_) => Ok(None),
+        }
+    }
 }
 
+
+/// Fields of structs which derive `FromApp` are converted by `FromApp::from_named` if their types implement `FromApp`
+/// (e.g., `Count`), otherwise they are converted from the first argument of the option by `FromArg`.
+/// The trait is chosen by auto-ref of `&&PhantomData<T>`, it's used by `#[derive(FromApp)]` only.
+#[doc(hidden)]
+pub trait FieldFromApp<'a, T> {
+    fn convert_field(&self, app: &'a Application, long: &str, ty: &str) -> Result<T, ParseError>;
+}
+
+impl<'a, T: FromApp<'a>> FieldFromApp<'a, T> for &PhantomData<T> {
+    fn convert_field(&self, app: &'a Application, long: &str, ty: &str) -> Result<T, ParseError> {
+        T::from_named(app, long).map_err(|err| T::parse_error(err, ty))
+    }
+}
+
+/// See `FieldFromApp`, errors report the argument failed to be converted (or the option if the argument is absent)
+/// and where it comes from (see `ParseError::with_source`).
+#[doc(hidden)]
+pub trait FieldFromArg<'a, T> {
+    fn convert_field(&self, app: &'a Application, long: &str, ty: &str) -> Result<T, ParseError>;
+}
+
+impl<'a, T: for<'b> FromArg<'b>> FieldFromArg<'a, T> for PhantomData<T> {
+    fn convert_field(&self, app: &'a Application, long: &str, ty: &str) -> Result<T, ParseError> {
+        let args = app.get_opt_args(long);
+        let arg = args.as_ref().and_then(|args| args.first());
+        let result = match arg {
+            Some(arg) => T::from_arg(arg),
+            None => T::from_absent(),
+        };
+
+        if let Ok(value) = result {
+            return Ok(value);
+        }
+
+        let token = arg.map_or_else(|| format!("--{}", long), |arg| arg.to_string());
+        let err = match T::possible_values() {
+            Some(values) => ParseError::InvalidValue {
+                token,
+                possible_values: values.into_iter().map(String::from).collect(),
+                index: None,
+            },
+            None => ParseError::ConversionFailed { token, ty: ty.to_string(), index: None },
+        };
+
+        Err(err.with_source(app.get_opt_source(long)))
+    }
+}
@@ -1,21 +1,362 @@
+use crate::parser::Source;
 use colored::Colorize;
-use std::process::exit;
+use std::ffi::OsStr;
+use std::fmt;
 
 // these all are runtime error
 pub const MISMATCHED_ARGS: &str = "Mismatched arguments.";
+pub const MISSING_ARGS: &str = "Missing required arguments.";
+pub const TOO_MANY_ARGS: &str = "Too many arguments.";
 pub const UNKNOWN_SUB: &str = "Unknown sub-command:";
 pub const UNKNOWN_OPT: &str = "Unknown option:";
+pub const REPEATED_OPT: &str = "Repeated option:";
+pub const MISSING_REQUIRED_OPT: &str = "Missing required option:";
+pub const CONFLICTING_OPTS: &str = "Conflicting options:";
+pub const MISSING_DEPENDENCY: &str = "Missing dependency:";
+pub const GROUP_VIOLATION: &str = "Invalid options of group";
+pub const CONVERSION_FAILED: &str = "Parse failed,";
+pub const INVALID_VALUE: &str = "Invalid value,";
+pub const DID_YOU_MEAN: &str = "Did you mean";
+pub const INVALID_SPEC: &str = "Invalid spec,";
+pub const INVALID_CONFIG: &str = "Invalid config file";
+pub const INVALID_ENV: &str = "Invalid environment variable";
+pub const INVALID_RESPONSE_FILE: &str = "Invalid response file";
 pub const INTERNAL_ERROR: &str = "Internal error, give us feedback on Github pls";
 
+/// Errors raised while parsing the input of cli.
+///
+/// `token` is the offending input, e.g., `--unknown-option`.
+/// `index` is the position of `token` in argv (the path of cli is at `0`),
+/// it's `None` if the position is unknown (e.g., the segments are not parsed from argv).
+/// If response files are expanded, it's the position in the expanded argv
+/// (except `InvalidResponseFile`, which is located at the `@file` in argv offered).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// `token` is `-s` or `--long`.
+    /// `suggestion` is the most similar option defined, e.g., `--force` for `--forse`.
+    UnknownOption { token: String, suggestion: Option<String>, index: Option<usize> },
+    /// `suggestion` is the most similar sub-command defined, e.g., `install` for `instal`.
+    UnknownSubCommand { token: String, suggestion: Option<String>, index: Option<usize> },
+    /// `token` is `-s` or `--long`, the option can only be offered once (see `RepeatPolicy::Error`).
+    RepeatedOption { token: String, index: Option<usize> },
+    /// `token` is `--long`, `cmd` is the name of command or the path of sub-command which requires the option.
+    /// `index` is always `None` because the option isn't offered.
+    MissingRequiredOption { token: String, cmd: String, index: Option<usize> },
+    /// `token` is `--long`, it's required because none of `unless` (options or arguments) is offered.
+    /// `index` is always `None` because the option isn't offered.
+    MissingConditionalOption { token: String, unless: Vec<String>, index: Option<usize> },
+    /// `token` is `--long`, it can't be offered together with `conflict` (an option or argument).
+    ConflictingOptions { token: String, conflict: String, index: Option<usize> },
+    /// `token` is `--long`, it requires `required` (an option or argument) which isn't offered.
+    MissingDependency { token: String, required: String, index: Option<usize> },
+    /// `members` are options of `group`. `token` is the redundant option if only one of them is accepted,
+    /// it's empty if none of them is offered (then `index` is always `None`).
+    GroupViolation { token: String, group: String, members: Vec<String>, index: Option<usize> },
+    /// `token` is the name (or the path if it's nested) of sub-command, or the name of command.
+    /// `expected` is the defined arguments, e.g., `<a> <b> [c]`.
+    MissingArgument { token: String, expected: String, index: Option<usize> },
+    /// `token` is the first redundant input.
+    TooManyArguments { token: String, expected: String, index: Option<usize> },
+    /// `token` is `-s` or `--long`, `inputs` are the arguments offered for it.
+    BadOptionArgument { token: String, expected: String, inputs: Vec<String>, index: Option<usize> },
+    /// `ty` is the name of the type which `token` should be converted to.
+    ConversionFailed { token: String, ty: String, index: Option<usize> },
+    /// `token` isn't one of `possible_values` (see `FromArg::possible_values`).
+    InvalidValue { token: String, possible_values: Vec<String>, index: Option<usize> },
+    /// `token` is the path of config file, `key` is the dotted path of the offending key (e.g., `install.global`),
+    /// it's empty if the file can't be read.
+    InvalidConfig { token: String, key: String, message: String, index: Option<usize> },
+    /// `token` is the environment variable which the value of option is read from (see `Options::env`).
+    /// `index` is always `None` because the value isn't offered in argv.
+    InvalidEnv { token: String, message: String, index: Option<usize> },
+    /// `token` is the response file in argv, e.g., `@args.txt`, `path` is the file which can't be read or parsed,
+    /// it's different from `token` if the file is included by other response files.
+    /// `line` is the line (starts from `1`) where the error is found, it's `None` if the file can't be read.
+    InvalidResponseFile { token: String, path: String, line: Option<usize>, message: String, index: Option<usize> },
+    /// It should never happen, `String` is the position in the source code.
+    Internal(String),
+}
 
-// this will panic!
-pub fn raise_error(msg: String) -> bool {
-    if !cfg!(feature = "test") {
-        let prefix = "CLI runtime error: ".bold().red();
-        // output to the standard error pipe
-        eprintln!("\n{}{}\n", prefix, msg);
-        exit(1);
+impl ParseError {
+    pub fn token(&self) -> &str {
+        match self {
+            ParseError::UnknownOption { token, .. }
+            | ParseError::UnknownSubCommand { token, .. }
+            | ParseError::RepeatedOption { token, .. }
+            | ParseError::MissingRequiredOption { token, .. }
+            | ParseError::MissingConditionalOption { token, .. }
+            | ParseError::ConflictingOptions { token, .. }
+            | ParseError::MissingDependency { token, .. }
+            | ParseError::GroupViolation { token, .. }
+            | ParseError::MissingArgument { token, .. }
+            | ParseError::TooManyArguments { token, .. }
+            | ParseError::BadOptionArgument { token, .. }
+            | ParseError::ConversionFailed { token, .. }
+            | ParseError::InvalidValue { token, .. }
+            | ParseError::InvalidConfig { token, .. }
+            | ParseError::InvalidEnv { token, .. }
+            | ParseError::InvalidResponseFile { token, .. } => token,
+            ParseError::Internal(_) => "",
+        }
     }
 
-    false
-}
\ No newline at end of file
+    pub fn index(&self) -> Option<usize> {
+        match self {
+            ParseError::UnknownOption { index, .. }
+            | ParseError::UnknownSubCommand { index, .. }
+            | ParseError::RepeatedOption { index, .. }
+            | ParseError::MissingRequiredOption { index, .. }
+            | ParseError::MissingConditionalOption { index, .. }
+            | ParseError::ConflictingOptions { index, .. }
+            | ParseError::MissingDependency { index, .. }
+            | ParseError::GroupViolation { index, .. }
+            | ParseError::MissingArgument { index, .. }
+            | ParseError::TooManyArguments { index, .. }
+            | ParseError::BadOptionArgument { index, .. }
+            | ParseError::ConversionFailed { index, .. }
+            | ParseError::InvalidValue { index, .. }
+            | ParseError::InvalidConfig { index, .. }
+            | ParseError::InvalidEnv { index, .. }
+            | ParseError::InvalidResponseFile { index, .. } => *index,
+            ParseError::Internal(_) => None,
+        }
+    }
+
+    /// Set the position of `token` in argv, see `index`.
+    pub fn with_index(mut self, at: Option<usize>) -> Self {
+        match &mut self {
+            ParseError::UnknownOption { index, .. }
+            | ParseError::UnknownSubCommand { index, .. }
+            | ParseError::RepeatedOption { index, .. }
+            | ParseError::MissingRequiredOption { index, .. }
+            | ParseError::MissingConditionalOption { index, .. }
+            | ParseError::ConflictingOptions { index, .. }
+            | ParseError::MissingDependency { index, .. }
+            | ParseError::GroupViolation { index, .. }
+            | ParseError::MissingArgument { index, .. }
+            | ParseError::TooManyArguments { index, .. }
+            | ParseError::BadOptionArgument { index, .. }
+            | ParseError::ConversionFailed { index, .. }
+            | ParseError::InvalidValue { index, .. }
+            | ParseError::InvalidConfig { index, .. }
+            | ParseError::InvalidEnv { index, .. }
+            | ParseError::InvalidResponseFile { index, .. } => *index = at,
+            ParseError::Internal(_) => {},
+        }
+
+        self
+    }
+
+    /// Values of options may be read from environment variables or the config file (see `Application::get_opt_source`),
+    /// if they fail to be converted (i.e., `ConversionFailed` and `InvalidValue`), the error is turned into
+    /// `InvalidEnv` or `InvalidConfig` which reports where the value comes from, other errors are returned as they are.
+    pub fn with_source(self, source: Option<Source>) -> Self {
+        let message = match &self {
+            ParseError::ConversionFailed { token, ty, .. } => format!("`{}` can't be parsed as type `{}`", token, ty),
+            ParseError::InvalidValue { token, possible_values, .. } => format!(
+                "`{}` isn't accepted, possible values are `{}`",
+                token, possible_values.join(", "),
+            ),
+            _ => return self,
+        };
+
+        match source {
+            Some(Source::Env(env)) => ParseError::InvalidEnv { token: env, message, index: None },
+            Some(Source::Config { path, key }) => ParseError::InvalidConfig {
+                token: path.to_string_lossy().to_string(),
+                message: format!("value of `{}`, {}", key, message),
+                key,
+                index: None,
+            },
+            _ => self,
+        }
+    }
+
+    /// Errors of parsing are located by positions of segments (see `parser::Segment`) when they are raised.
+    /// But values are converted after parsing, if the position of a value which fails to be converted
+    /// (i.e., `ConversionFailed` and `InvalidValue`) is unknown, find it in `argv`, other errors are returned as they are.
+    /// Note, the first element of `argv` should be the path of cli,
+    /// and `argv` should be expanded from response files (see `SegmentWrapper::parse_argv`).
+    pub fn locate<T: AsRef<OsStr>>(self, argv: &[T]) -> Self {
+        if self.index().is_some() || !matches!(self, ParseError::ConversionFailed { .. } | ParseError::InvalidValue { .. }) {
+            return self;
+        }
+
+        let found = argv.iter().skip(1).position(|arg| arg.as_ref().to_string_lossy() == self.token()).map(|idx| idx + 1);
+
+        self.with_index(found)
+    }
+}
+
+fn fmt_suggestion(f: &mut fmt::Formatter<'_>, suggestion: &Option<String>) -> fmt::Result {
+    if let Some(suggestion) = suggestion {
+        write!(f, ". {} `{}`?", DID_YOU_MEAN, suggestion.bold())
+    } else {
+        Ok(())
+    }
+}
+
+// Levenshtein distance, i.e., the least number of insertions, deletions and substitutions
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+
+        prev = curr;
+    }
+
+    prev[b.len()]
+}
+
+/// Find the candidate which is the most similar to `token`, it's used to suggest if `token` is unknown.
+/// Return `None` if no candidate is similar enough, i.e., more than a third of characters are different.
+pub fn suggest<'a, I: IntoIterator<Item = &'a str>>(token: &str, candidates: I) -> Option<String> {
+    let threshold = (token.chars().count() / 3).max(1);
+
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(token, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownOption { token, suggestion, .. } => {
+                write!(f, "{} `{}`", UNKNOWN_OPT, token.bold())?;
+                fmt_suggestion(f, suggestion)
+            }
+            ParseError::UnknownSubCommand { token, suggestion, .. } => {
+                write!(f, "{} `{}`", UNKNOWN_SUB, token.bold())?;
+                fmt_suggestion(f, suggestion)
+            }
+            ParseError::RepeatedOption { token, .. } => write!(f, "{} `{}` can only be offered once.", REPEATED_OPT, token.bold()),
+            ParseError::MissingRequiredOption { token, cmd, .. } => write!(
+                f,
+                "{} `{}` is required by `{}`.",
+                MISSING_REQUIRED_OPT, token.bold(), cmd.bold(),
+            ),
+            ParseError::MissingConditionalOption { token, unless, .. } => write!(
+                f,
+                "{} `{}` is required unless one of `{}` is offered.",
+                MISSING_REQUIRED_OPT, token.bold(), unless.join(", ").bold(),
+            ),
+            ParseError::ConflictingOptions { token, conflict, .. } => write!(
+                f,
+                "{} `{}` can't be used together with `{}`.",
+                CONFLICTING_OPTS, token.bold(), conflict.bold(),
+            ),
+            ParseError::MissingDependency { token, required, .. } => write!(
+                f,
+                "{} `{}` requires `{}`, but it isn't offered.",
+                MISSING_DEPENDENCY, token.bold(), required.bold(),
+            ),
+            ParseError::GroupViolation { token, group, members, .. } => if token.is_empty() {
+                write!(
+                    f,
+                    "{} `{}`: one of `{}` is required.",
+                    GROUP_VIOLATION, group.bold(), members.join(", ").bold(),
+                )
+            } else {
+                write!(
+                    f,
+                    "{} `{}`: only one of `{}` is accepted, but `{}` is redundant.",
+                    GROUP_VIOLATION, group.bold(), members.join(", ").bold(), token.bold(),
+                )
+            },
+            ParseError::MissingArgument { token, expected, .. } => write!(
+                f,
+                "{} Arguments of `{}` are defined as `{}`.",
+                MISSING_ARGS, token.bold(), expected.bold(),
+            ),
+            ParseError::TooManyArguments { token, expected, .. } => if expected.is_empty() {
+                write!(f, "{} No argument is accepted, but you input `{}`.", TOO_MANY_ARGS, token.bold())
+            } else {
+                write!(
+                    f,
+                    "{} Arguments are defined as `{}`, but `{}` is redundant.",
+                    TOO_MANY_ARGS, expected.bold(), token.bold(),
+                )
+            },
+            ParseError::BadOptionArgument { token, expected, inputs, .. } => if inputs.is_empty() {
+                write!(
+                    f,
+                    "{} Arguments of `{}` are defined as `{}`, but you input nothing.",
+                    MISMATCHED_ARGS, token.bold(), expected.bold(),
+                )
+            } else if expected.is_empty() {
+                write!(
+                    f,
+                    "{} `{}` doesn't accept any argument, but you input `{}`.",
+                    MISMATCHED_ARGS, token.bold(), inputs.join(", ").bold(),
+                )
+            } else {
+                write!(
+                    f,
+                    "{} Arguments of `{}` are defined as `{}`, but you input `{}`.",
+                    MISMATCHED_ARGS, token.bold(), expected.bold(), inputs.join(", ").bold(),
+                )
+            },
+            ParseError::ConversionFailed { token, ty, .. } => write!(
+                f,
+                "{} can't parse input `{}` as type `{}`.",
+                CONVERSION_FAILED, token.bold(), ty.bold(),
+            ),
+            ParseError::InvalidValue { token, possible_values, .. } => write!(
+                f,
+                "{} `{}` isn't accepted, possible values are `{}`.",
+                INVALID_VALUE, token.bold(), possible_values.join(", ").bold(),
+            ),
+            ParseError::InvalidConfig { token, message, .. } => write!(f, "{} `{}`: {}.", INVALID_CONFIG, token.bold(), message),
+            ParseError::InvalidEnv { token, message, .. } => write!(f, "{} `{}`: {}.", INVALID_ENV, token.bold(), message),
+            ParseError::InvalidResponseFile { path, line, message, .. } => match line {
+                Some(line) => write!(f, "{} `{}` (line {}): {}.", INVALID_RESPONSE_FILE, path.bold(), line, message),
+                None => write!(f, "{} `{}`: {}.", INVALID_RESPONSE_FILE, path.bold(), message),
+            },
+            ParseError::Internal(position) => write!(f, "{} Position: {}", INTERNAL_ERROR, position),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Errors raised while parsing specs of options, arguments, sub-commands or command at runtime (see `spec`).
+///
+/// `position` is the byte offset in `spec` where the error is found, it's the length of `spec` if it ends unexpectedly.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpecError {
+    pub spec: String,
+    pub position: usize,
+    pub message: String,
+}
+
+impl SpecError {
+    pub fn new<T: Into<String>>(spec: &str, position: usize, message: T) -> Self {
+        SpecError { spec: spec.to_string(), position, message: message.into() }
+    }
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} at position {} of `{}`.", INVALID_SPEC, self.message, self.position, self.spec.bold())
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+/// Output the error to the standard error pipe in the style of runtime error.
+pub fn report_error<T: fmt::Display>(err: &T) {
+    let prefix = "CLI runtime error: ".bold().red();
+
+    eprintln!("\n{}{}\n", prefix, err);
+}
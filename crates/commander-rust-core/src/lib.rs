@@ -2,6 +2,13 @@ pub mod parser;
 pub mod traits;
 pub mod errors;
 pub mod converters;
+pub mod completions;
+pub mod man;
+pub mod builder;
+pub mod spec;
+pub mod config;
+pub mod response;
+pub mod register;
 
 #[cfg(feature = "test")]
 use regex::Regex;
@@ -9,9 +16,11 @@ use regex::Regex;
 use traits::{GetArgs, ValidateArgs, PushOptions};
 use std::collections::HashSet;
 use std::fmt;
-use traits::{PushSubCommand, PushArgument};
+use traits::{PushSubCommand, PushArgument, SetPossibleValues};
 use std::option::Option::Some;
-use crate::traits::{GetOpts, GetOpt };
+use crate::traits::{GetOpts, GetOpt, GetSubCmds};
+use crate::parser::Segment;
+use crate::config::Config;
 use colored::Colorize;
 
 /// Note: These `struct`s are different from `struct`s with same names
@@ -43,21 +52,91 @@ impl ArgumentType {
 pub struct Argument {
     pub name: String,
     pub ty: ArgumentType,
+    // used if the argument is not offered, e.g., `[host = "localhost"]`
+    pub default: Option<String>,
+    // values accepted by the argument, e.g., `<food: noodles|beef|fish>`, `None` means any value is accepted
+    pub possible_values: Option<Vec<String>>,
 }
 
 impl fmt::Display for Argument {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = if let Some(values) = &self.possible_values {
+            format!("{}: {}", self.name, values.join("|"))
+        } else {
+            self.name.clone()
+        };
         let s = match self.ty {
-            ArgumentType::RequiredSingle => format!("<{}>", self.name),
-            ArgumentType::RequiredMultiple => format!("<..{}>", self.name),
-            ArgumentType::OptionalSingle => format!("[{}]", self.name),
-            ArgumentType::OptionalMultiple => format!("[..{}]", self.name),
+            ArgumentType::RequiredSingle => format!("<{}>", name),
+            ArgumentType::RequiredMultiple => format!("<..{}>", name),
+            ArgumentType::OptionalSingle => format!("[{}]", name),
+            ArgumentType::OptionalMultiple => format!("[..{}]", name),
         };
 
         write!(f, "{}", s)
     }
 }
 
+// e.g., `[default: 8080]`, or `[default: from = 1, to = 2]` if there are several arguments
+fn fmt_defaults(args: &[Argument]) -> Option<String> {
+    let defaults: Vec<(&String, &String)> = args
+        .iter()
+        .filter_map(|arg| arg.default.as_ref().map(|default| (&arg.name, default)))
+        .collect();
+
+    if defaults.is_empty() {
+        None
+    } else if args.len() == 1 {
+        Some(format!("[default: {}]", defaults[0].1))
+    } else {
+        let defaults: Vec<String> = defaults
+            .into_iter()
+            .map(|(name, default)| format!("{} = {}", name, default))
+            .collect();
+
+        Some(format!("[default: {}]", defaults.join(", ")))
+    }
+}
+
+// list arguments which have default values
+fn fmt_args_defaults(f: &mut fmt::Formatter<'_>, args: &[Argument]) -> fmt::Result {
+    let tab = String::from("    ");
+    let args: Vec<&Argument> = args.iter().filter(|arg| arg.default.is_some()).collect();
+
+    if args.is_empty() {
+        return Ok(());
+    }
+
+    let mut width = 0;
+
+    for arg in args.iter() {
+        width = width.max(arg.to_string().len());
+    }
+
+    writeln!(f, "{}:", "ARGUMENTS".bold().italic()).unwrap();
+
+    for arg in args {
+        let defaults = fmt_defaults(std::slice::from_ref(arg)).unwrap_or_default();
+
+        writeln!(f, "{tab}{:<width$}{tab}{}", arg.to_string(), defaults, width = width, tab = tab).unwrap();
+    }
+
+    writeln!(f)
+}
+
+// name and aliases of sub-command shown in help information, e.g., `install, i`
+fn fmt_sub_cmd_names(sub_cmd: &SubCommand) -> String {
+    let mut names = vec![sub_cmd.name.as_str()];
+
+    names.extend(sub_cmd.aliases.iter().map(String::as_str));
+    names.join(", ")
+}
+
+fn set_possible_values(args: &mut [Argument], name: &str, values: Option<Vec<&str>>) {
+    if let Some(arg) = args.iter_mut().find(|arg| arg.name == name) {
+        arg.possible_values = values.map(|values| values.into_iter().map(String::from).collect());
+    }
+}
+
 #[cfg(feature = "test")]
 impl From<String> for Argument {
     fn from(s: String) -> Self {
@@ -70,26 +149,36 @@ impl From<String> for Argument {
             Argument {
                 name: rs_re["name"].to_string(),
                 ty: ArgumentType::RequiredSingle,
+                default: None,
+                possible_values: None,
             }
         } else if let Some(rm_re) = rm_re {
             Argument {
                 name: rm_re["name"].to_string(),
                 ty: ArgumentType::RequiredMultiple,
+                default: None,
+                possible_values: None,
             }
         } else if let Some(os_re) = os_re {
             Argument {
                 name: os_re["name"].to_string(),
                 ty: ArgumentType::OptionalSingle,
+                default: None,
+                possible_values: None,
             }
         } else if let Some(om_re) = om_re {
             Argument {
                 name: om_re["name"].to_string(),
                 ty: ArgumentType::OptionalMultiple,
+                default: None,
+                possible_values: None,
             }
         } else {
             Argument {
                 name: String::new(),
                 ty: ArgumentType::OptionalSingle,
+                default: None,
+                possible_values: None,
             }
         }
     }
@@ -102,12 +191,65 @@ impl From<&str> for Argument {
     }
 }
 
+/// What to do if an option is offered more than once, e.g., `-I a -I b`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum RepeatPolicy {
+    /// The last occurrence overwrites previous ones.
+    #[default]
+    LastWins,
+    /// The first occurrence is kept, others are ignored.
+    FirstWins,
+    /// Arguments of all occurrences are accumulated in order, e.g., `<dir>` of `-I a -I b` is `a b`.
+    Append,
+    /// Offering the option more than once is a parse error.
+    Error,
+}
+
+/// How many options of a named group must be offered.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GroupKind {
+    /// Exactly one option of the group is offered, e.g., one of `--json` and `--yaml`.
+    ExactlyOne,
+    /// At least one option of the group is offered.
+    AtLeastOne,
+}
+
+/// Named group which the option belongs to. Options of the same group should use the same kind.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OptionGroup {
+    pub name: String,
+    pub kind: GroupKind,
+}
+
+impl fmt::Display for OptionGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            GroupKind::ExactlyOne => write!(f, "{}, exactly one", self.name),
+            GroupKind::AtLeastOne => write!(f, "{}, at least one", self.name),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq)]
 pub struct Options {
     pub short: Option<String>,
     pub long: String,
     opt_args: Vec<Argument>,
     pub desc: Option<String>,
+    // name of environment variable which is used if the option is not offered, e.g., `APP_TOKEN`
+    pub env: Option<String>,
+    pub repeat: RepeatPolicy,
+    // flag which accepts the negation, e.g., `--no-color` of `--[no-]color`
+    pub negatable: bool,
+    // the option must be offered (or read from its environment variable)
+    pub required: bool,
+    // names of options or arguments which can't be offered together with the option
+    pub conflicts_with: Vec<String>,
+    // names of options or arguments which must be offered if the option is offered
+    pub requires: Vec<String>,
+    // the option must be offered unless one of these options or arguments is offered
+    pub required_unless: Vec<String>,
+    pub group: Option<OptionGroup>,
 }
 
 impl Options {
@@ -117,6 +259,71 @@ impl Options {
             long,
             opt_args: vec![],
             desc,
+            env: None,
+            repeat: RepeatPolicy::LastWins,
+            negatable: false,
+            required: false,
+            conflicts_with: vec![],
+            requires: vec![],
+            required_unless: vec![],
+            group: None,
+        }
+    }
+
+    /// Whether `long` is the negation of the option, e.g., `no-color` of `--[no-]color`.
+    pub fn is_negated(&self, long: &str) -> bool {
+        self.negatable && long.strip_prefix("no-") == Some(self.long.as_str())
+    }
+
+    // whether the option (or its negation) is one of `offered`
+    pub(crate) fn is_offered(&self, offered: &[Segment]) -> bool {
+        offered.iter().any(|seg| match seg {
+            Segment::Long(name, _, _) => name == &self.long || self.is_negated(name),
+            Segment::Short(name, _, _) => Some(name) == self.short.as_ref(),
+            _ => false,
+        })
+    }
+
+    // long name shown in help information, e.g., `--[no-]color`
+    pub(crate) fn fmt_long(&self) -> String {
+        if self.negatable {
+            format!("--[no-]{}", self.long)
+        } else {
+            format!("--{}", self.long)
+        }
+    }
+
+    // description shown in help information, including default values of arguments, environment variable
+    // and its constraints
+    pub(crate) fn help_desc(&self) -> Option<String> {
+        let env = self.env.as_ref().map(|env| format!("[env: {}]", env));
+        let required = if self.required { Some(String::from("[required]")) } else { None };
+        let fmt_names = |label: &str, names: &Vec<String>| {
+            if names.is_empty() {
+                None
+            } else {
+                Some(format!("[{}: {}]", label, names.join(", ")))
+            }
+        };
+        let group = self.group.as_ref().map(|group| format!("[group: {}]", group));
+        let parts: Vec<String> = vec![
+            self.desc.clone(),
+            fmt_defaults(&self.opt_args),
+            env,
+            required,
+            fmt_names("required unless", &self.required_unless),
+            fmt_names("conflicts with", &self.conflicts_with),
+            fmt_names("requires", &self.requires),
+            group,
+        ]
+            .into_iter()
+            .flatten()
+            .collect();
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" "))
         }
     }
 }
@@ -131,7 +338,7 @@ impl GetArgs for Options {
 impl From<String> for Options {
     fn from(s: String) -> Self {
         let short_pat = r"\-(?P<short>[[:alpha:]])";
-        let long_pat = r"\-\-(?P<long>(-?[[:word:]])+)";
+        let long_pat = r"\-\-(?P<negatable>\[no-\])?(?P<long>(-?[[:word:]])+)";
         let desc_pat = r#""(?P<desc>.*)""#;
         let pattern = format!("({}, )?{}( (?P<args>[^,]+))?(, {})?", short_pat, long_pat, desc_pat);
         let re = Regex::new(&pattern).unwrap();
@@ -144,6 +351,8 @@ impl From<String> for Options {
         } else { None };
         let mut options = Options::new(short, cap["long"].to_string(), desc);
 
+        options.negatable = cap.name("negatable").is_some();
+
         if cap.name("args").is_some() {
             let args_s = &s[cap.name("args").unwrap().range()];
             let args: Vec<&str> = args_s.split_terminator(' ').collect();
@@ -189,8 +398,12 @@ pub struct SubCommand {
     // only using for display information
     pub belong: String,
     pub name: String,
+    // other names of the sub-command, e.g., `i` of `install`
+    pub aliases: Vec<String>,
     cmd_args: Vec<Argument>,
     options: Vec<Options>,
+    // nested sub-commands, e.g., `add` of `git remote add`
+    sub_cmds: Vec<SubCommand>,
     pub desc: Option<String>,
 }
 
@@ -199,12 +412,23 @@ impl SubCommand {
         SubCommand {
             belong,
             name,
+            aliases: vec![],
             cmd_args: vec![],
             options: vec![],
+            sub_cmds: vec![],
             desc,
         }
     }
 
+    /// Whether `name` is the name or one of aliases of the sub-command.
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name == name || self.aliases.iter().any(|alias| alias == name)
+    }
+
+    pub fn get_sub_cmd<'a>(&'a self, sub_name: &str) -> Option<&'a SubCommand> {
+        self.sub_cmds.iter().find(|sub_cmd| sub_cmd.is_named(sub_name))
+    }
+
     pub fn println(&self) {
         println!("{}", self);
     }
@@ -213,8 +437,8 @@ impl SubCommand {
 #[cfg(feature = "test")]
 impl From<String> for SubCommand {
     fn from(str: String) -> Self {
-        // pattern: [main -> sub <a> <b> [c], ]["hello world!"]
-        let re = Regex::new(r#"^((?P<belong>(\w|_)+) -> (?P<name>(\w|_)+)( (?P<args>[^,]+))?)?(, )?("(?P<desc>.*)")?$"#).unwrap();
+        // pattern: [main -> sub[|alias] <a> <b> [c], ]["hello world!"]
+        let re = Regex::new(r#"^((?P<belong>(\w|_)+) -> (?P<name>(\w|_)+)(?P<aliases>(\|(\w|_)+)*)( (?P<args>[^,]+))?)?(, )?("(?P<desc>.*)")?$"#).unwrap();
         let cap = re.captures(&str).unwrap();
         let mut belong = String::new();
         let mut name = String::new();
@@ -230,6 +454,10 @@ impl From<String> for SubCommand {
         }
         let mut sub_command = SubCommand::new(belong, name, desc);
 
+        if let Some(aliases) = cap.name("aliases") {
+            sub_command.aliases = aliases.as_str().split('|').skip(1).map(String::from).collect();
+        }
+
         if cap.name("args").is_some() {
             let args_s = &str[cap.name("args").unwrap().range()];
             let args: Vec<&str> = args_s.split_terminator(' ').collect();
@@ -273,12 +501,20 @@ impl fmt::Display for SubCommand {
 
         writeln!(f, "\n{}:", "USAGE".bold().italic()).unwrap();
 
+        let sub_cmd_fmt = if !self.sub_cmds.is_empty() {
+            " [sub_commands]"
+        } else {
+            ""
+        };
+
         if self.options.is_empty() {
-            writeln!(f, "{tab}{} {}{}\n", self.belong, self.name, args, tab = tab).unwrap();
+            writeln!(f, "{tab}{} {}{}{}\n", self.belong, self.name, args, sub_cmd_fmt, tab = tab).unwrap();
         } else {
-            writeln!(f, "{tab}{} {}{} [--options]\n", self.belong, self.name, args, tab = tab).unwrap();
+            writeln!(f, "{tab}{} {}{}{} [--options]\n", self.belong, self.name, args, sub_cmd_fmt, tab = tab).unwrap();
         }
 
+        fmt_args_defaults(f, &self.cmd_args)?;
+
         if !self.options.is_empty() {
             writeln!(f, "{}:", "OPTIONS".bold().italic()).unwrap();
 
@@ -287,9 +523,9 @@ impl fmt::Display for SubCommand {
 
             for opt in &self.options {
                 let opt_name_str = if let Some(short_name) = &opt.short {
-                    format!("-{}, --{}", short_name, opt.long)
+                    format!("-{}, {}", short_name, opt.fmt_long())
                 } else {
-                    format!("{tab}--{}", opt.long, tab = tab)
+                    format!("{tab}{}", opt.fmt_long(), tab = tab)
                 };
                 let opt_args_str = if !opt.opt_args.is_empty() {
                     let mut opt_args = String::new();
@@ -310,7 +546,7 @@ impl fmt::Display for SubCommand {
             }
 
             for (idx, opt_str) in opts_str.into_iter().enumerate() {
-                if let Some(opt_desc) = &self.options[idx].desc {
+                if let Some(opt_desc) = self.options[idx].help_desc() {
                     writeln!(f, "{tab}{:<width$}{tab}{}", opt_str, opt_desc, width = width, tab = tab).unwrap();
                 } else {
                     writeln!(f, "{tab}{:<width$}", opt_str, width = width, tab = tab).unwrap();
@@ -318,6 +554,26 @@ impl fmt::Display for SubCommand {
             }
         }
 
+        if !self.sub_cmds.is_empty() {
+            let mut width = 0;
+
+            for sub_cmd in self.sub_cmds.iter() {
+                width = width.max(fmt_sub_cmd_names(sub_cmd).len() + 4);
+            }
+
+            writeln!(f, "\n{}:", "SUB_COMMANDS".bold().italic()).unwrap();
+
+            for sub_cmd in self.sub_cmds.iter() {
+                writeln!(
+                    f,
+                    "{tab}{:<width$}{}",
+                    fmt_sub_cmd_names(sub_cmd),
+                    sub_cmd.desc.as_ref().unwrap_or(&String::new()),
+                    tab = tab, width = width
+                ).unwrap();
+            }
+        }
+
         writeln!(f)
     }
 }
@@ -332,6 +588,10 @@ impl GetOpts for SubCommand {
     fn get_opts(&self) -> &Vec<Options> { &self.options }
 }
 
+impl GetSubCmds for SubCommand {
+    fn get_sub_cmds(&self) -> &Vec<SubCommand> { &self.sub_cmds }
+}
+
 impl PushOptions for SubCommand {
     fn push_option(&mut self, option: Options) {
         if !option.validate_args() {
@@ -353,6 +613,23 @@ impl PushOptions for SubCommand {
     }
 }
 
+impl PushSubCommand for SubCommand {
+    fn push_sub_command(&mut self, sub_command: SubCommand) {
+        let mut dup = false;
+
+        for sub_cmd in self.sub_cmds.iter() {
+            if sub_cmd == &sub_command {
+                dup = true;
+                break;
+            }
+        }
+
+        if !dup {
+            self.sub_cmds.push(sub_command);
+        }
+    }
+}
+
 impl PushArgument for SubCommand {
     fn push_argument(&mut self, argument: Argument) {
         self.cmd_args.push(argument);
@@ -363,6 +640,24 @@ impl PushArgument for SubCommand {
     }
 }
 
+impl SetPossibleValues for SubCommand {
+    fn set_possible_values(&mut self, arg: &str, values: Option<Vec<&str>>) {
+        set_possible_values(&mut self.cmd_args, arg, values);
+    }
+}
+
+/// How to parse inputs which look like negative numbers, e.g., `-5` of `calc add -5 3`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum NegativeNumbers {
+    /// Negative numbers are values unless all of their digits are short options defined.
+    #[default]
+    Auto,
+    /// Negative numbers are always values.
+    Values,
+    /// Negative numbers are always short options, e.g., `-12` is `-1 -2`.
+    Options,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 pub struct Command {
     pub name: String,
@@ -371,6 +666,11 @@ pub struct Command {
     options: Vec<Options>,
     pub desc: Option<String>,
     pub version: String,
+    pub negative_numbers: NegativeNumbers,
+    // where to find the config file, values of options not offered are read from it
+    pub config: Option<Config>,
+    // whether `@file` in argv is expanded into arguments read from the file, see `response`
+    pub response_files: bool,
 }
 
 
@@ -383,12 +683,15 @@ impl Command {
             options: vec![],
             desc,
             version: String::from(std::env!("CARGO_PKG_VERSION")),
+            negative_numbers: NegativeNumbers::Auto,
+            config: None,
+            response_files: false,
         }
     }
 
     pub fn get_sub_cmd<'a>(&'a self, sub_name: &str) -> Option<&'a SubCommand> {
         for sub_cmd in &self.sub_cmds {
-            if sub_name == sub_cmd.name {
+            if sub_cmd.is_named(sub_name) {
                 return Some(sub_cmd);
             }
         }
@@ -396,6 +699,31 @@ impl Command {
         None
     }
 
+    /// Find the sub-command through its path, the path consists of names of nested sub-commands separated by spaces,
+    /// e.g., `remote add` means sub-command `add` of sub-command `remote`.
+    pub fn find_sub_cmd<'a>(&'a self, sub_path: &str) -> Option<&'a SubCommand> {
+        let mut names = sub_path.split(' ');
+        let mut sub_cmd = self.get_sub_cmd(names.next()?)?;
+
+        for name in names {
+            sub_cmd = sub_cmd.get_sub_cmd(name)?;
+        }
+
+        Some(sub_cmd)
+    }
+
+    pub(crate) fn find_sub_cmd_mut(&mut self, sub_path: &str) -> Option<&mut SubCommand> {
+        let mut names = sub_path.split(' ');
+        let first = names.next()?;
+        let mut sub_cmd = self.sub_cmds.iter_mut().find(|sub_cmd| sub_cmd.is_named(first))?;
+
+        for name in names {
+            sub_cmd = sub_cmd.sub_cmds.iter_mut().find(|sub_cmd| sub_cmd.is_named(name))?;
+        }
+
+        Some(sub_cmd)
+    }
+
     pub fn println(&self) {
         println!("{}", self);
     }
@@ -405,7 +733,7 @@ impl Command {
     }
 
     pub fn println_sub<T: ToString>(&self, key: T) {
-        if let Some(sub_cmd) = self.get_sub_cmd(&key.to_string()) {
+        if let Some(sub_cmd) = self.find_sub_cmd(&key.to_string()) {
             sub_cmd.println();
         }
     }
@@ -487,6 +815,7 @@ impl fmt::Display for Command {
         };
 
         writeln!(f, "{tab}{}{}{}{}\n", self.name, args, opt_fmt, sub_cmd_fmt, tab = tab).unwrap();
+        fmt_args_defaults(f, &self.cmd_args)?;
 
         if !self.options.is_empty() {
             writeln!(f, "{}:", "OPTIONS".bold().italic()).unwrap();
@@ -496,9 +825,9 @@ impl fmt::Display for Command {
 
             for opt in &self.options {
                 let opt_name_str = if let Some(short_name) = &opt.short {
-                    format!("-{}, --{}", short_name, opt.long)
+                    format!("-{}, {}", short_name, opt.fmt_long())
                 } else {
-                    format!("{tab}--{}", opt.long, tab = tab)
+                    format!("{tab}{}", opt.fmt_long(), tab = tab)
                 };
                 let opt_args_str = if !opt.opt_args.is_empty() {
                     let mut opt_args = String::new();
@@ -519,7 +848,7 @@ impl fmt::Display for Command {
             }
 
             for (idx, opt_str) in opts_str.into_iter().enumerate() {
-                if let Some(opt_desc) = &self.options[idx].desc {
+                if let Some(opt_desc) = self.options[idx].help_desc() {
                     writeln!(f, "{tab}{:<width$}{tab}{}", opt_str, opt_desc, width = width, tab = tab).unwrap();
                 } else {
                     writeln!(f, "{tab}{:<width$}", opt_str, width = width, tab = tab).unwrap();
@@ -531,7 +860,7 @@ impl fmt::Display for Command {
             let mut width = 0;
 
             for sub_cmd in self.sub_cmds.iter() {
-                width = width.max(sub_cmd.name.len() + 4);
+                width = width.max(fmt_sub_cmd_names(sub_cmd).len() + 4);
             }
 
             writeln!(f, "\n{}:", "SUB_COMMANDS".bold().italic()).unwrap();
@@ -540,7 +869,7 @@ impl fmt::Display for Command {
                 writeln!(
                     f,
                     "{tab}{:<width$}{}",
-                    sub_cmd.name,
+                    fmt_sub_cmd_names(sub_cmd),
                     sub_cmd.desc.as_ref().unwrap_or(&String::new()),
                     tab = tab, width = width
                 ).unwrap();
@@ -600,6 +929,12 @@ impl PushArgument for Command {
     }
 }
 
+impl SetPossibleValues for Command {
+    fn set_possible_values(&mut self, arg: &str, values: Option<Vec<&str>>) {
+        set_possible_values(&mut self.cmd_args, arg, values);
+    }
+}
+
 impl GetArgs for Command {
     fn get_args(&self) -> &Vec<Argument> {
         &self.cmd_args
@@ -612,6 +947,12 @@ impl GetOpts for Command {
     }
 }
 
+impl GetSubCmds for Command {
+    fn get_sub_cmds(&self) -> &Vec<SubCommand> {
+        &self.sub_cmds
+    }
+}
+
 impl<T: GetArgs> ValidateArgs for T {
     fn validate_args(&self) -> bool {
         let mut opt_start = false;
@@ -651,7 +992,7 @@ impl<T: GetArgs> ValidateArgs for T {
 impl<T: GetOpts> GetOpt for T {
     fn get_long_opt(&self, opt_name: &str) -> Option<&Options> {
         for opt in self.get_opts().iter() {
-            if opt_name == opt.long {
+            if opt_name == opt.long || opt.is_negated(opt_name) {
                 return Some(opt);
             }
         }
@@ -0,0 +1,169 @@
+use crate::{ Command, SubCommand, Options, Argument };
+
+// man pages are always in section 1 (user commands)
+const SECTION: &str = "1";
+
+// escape text so that it's printed literally by roff
+fn escape(s: &str) -> String {
+    s.split('\n')
+        .map(|line| {
+            // `"` would end quoted arguments of requests, e.g., `.TH`
+            let escaped = line.replace('\\', r"\e").replace('-', r"\-").replace('"', r"\(dq");
+
+            // lines begin with `.` or `'` are control lines
+            if escaped.starts_with('.') || escaped.starts_with('\'') {
+                format!(r"\&{}", escaped)
+            } else {
+                escaped
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn bold(s: &str) -> String {
+    format!(r"\fB{}\fR", escape(s))
+}
+
+fn args_str(args: &[Argument]) -> String {
+    args.iter()
+        .map(|arg| format!(" {}", escape(&arg.to_string())))
+        .collect()
+}
+
+fn render_options(page: &mut String, title: &str, options: &[Options]) {
+    if options.is_empty() {
+        return;
+    }
+
+    page.push_str(&format!(".SH {}\n", title));
+
+    for opt in options.iter() {
+        let name = if let Some(short) = &opt.short {
+            format!("{}, {}", bold(&format!("-{}", short)), bold(&opt.fmt_long()))
+        } else {
+            bold(&opt.fmt_long())
+        };
+
+        page.push_str(&format!(".TP\n{}{}\n", name, args_str(&opt.opt_args)));
+
+        if let Some(desc) = opt.help_desc() {
+            page.push_str(&format!("{}\n", escape(&desc)));
+        }
+    }
+}
+
+fn render_sub_cmds(page: &mut String, sub_cmds: &[SubCommand]) {
+    if sub_cmds.is_empty() {
+        return;
+    }
+
+    page.push_str(".SH SUB-COMMANDS\n");
+
+    for sub_cmd in sub_cmds.iter() {
+        let names: Vec<String> = std::iter::once(&sub_cmd.name).chain(sub_cmd.aliases.iter()).map(|name| bold(name)).collect();
+
+        page.push_str(&format!(".TP\n{}{}\n", names.join(", "), args_str(&sub_cmd.cmd_args)));
+
+        if let Some(desc) = &sub_cmd.desc {
+            page.push_str(&format!("{}\n", escape(desc)));
+        }
+    }
+}
+
+// `name` is the name of page, e.g., `git-remote-add`
+fn render_header(page: &mut String, name: &str, cmd: &Command, desc: &Option<String>) {
+    page.push_str(&format!(
+        ".TH {} {} \"\" \"{}\" \"User Commands\"\n",
+        escape(&name.to_uppercase()),
+        SECTION,
+        escape(&format!("{} {}", cmd.name, cmd.version)),
+    ));
+    page.push_str(".SH NAME\n");
+
+    if let Some(desc) = desc {
+        page.push_str(&format!("{} \\- {}\n", escape(name), escape(desc)));
+    } else {
+        page.push_str(&format!("{}\n", escape(name)));
+    }
+}
+
+/// Render the man page of `cmd` in `man(7)` format, the sub-commands are listed but not detailed.
+pub fn render(cmd: &Command) -> String {
+    let mut page = String::new();
+    let opt_fmt = if cmd.options.is_empty() { "" } else { r" [\fIoptions\fR]" };
+    let sub_cmd_fmt = if cmd.sub_cmds.is_empty() { "" } else { r" [\fIsub_commands\fR]" };
+
+    render_header(&mut page, &cmd.name, cmd, &cmd.desc);
+    page.push_str(".SH SYNOPSIS\n");
+    page.push_str(&format!("{}{}{}{}\n", bold(&cmd.name), args_str(&cmd.cmd_args), opt_fmt, sub_cmd_fmt));
+
+    if let Some(desc) = &cmd.desc {
+        page.push_str(&format!(".SH DESCRIPTION\n{}\n", escape(desc)));
+    }
+
+    render_options(&mut page, "OPTIONS", &cmd.options);
+    render_sub_cmds(&mut page, &cmd.sub_cmds);
+    page.push_str(&format!(".SH VERSION\n{}\n", escape(&cmd.version)));
+
+    page
+}
+
+/// Render the man page of the specified sub-command, `sub_path` is the same as it in `Command::find_sub_cmd`.
+/// The name of page consists of names of command and sub-commands, e.g., `git-remote-add`.
+/// Return `None` if the sub-command doesn't exist.
+pub fn render_sub(cmd: &Command, sub_path: &str) -> Option<String> {
+    let sub_cmd = cmd.find_sub_cmd(sub_path)?;
+    let mut page = String::new();
+    let name = format!("{}-{}", cmd.name, sub_path.replace(' ', "-"));
+    let opt_fmt = if sub_cmd.options.is_empty() && cmd.options.is_empty() { "" } else { r" [\fIoptions\fR]" };
+    let sub_cmd_fmt = if sub_cmd.sub_cmds.is_empty() { "" } else { r" [\fIsub_commands\fR]" };
+
+    render_header(&mut page, &name, cmd, &sub_cmd.desc);
+    page.push_str(".SH SYNOPSIS\n");
+    page.push_str(&format!(
+        "{} {}{}{}{}\n",
+        bold(&cmd.name),
+        bold(sub_path),
+        args_str(&sub_cmd.cmd_args),
+        opt_fmt,
+        sub_cmd_fmt,
+    ));
+
+    if let Some(desc) = &sub_cmd.desc {
+        page.push_str(&format!(".SH DESCRIPTION\n{}\n", escape(desc)));
+    }
+
+    render_options(&mut page, "OPTIONS", &sub_cmd.options);
+    render_options(&mut page, "GLOBAL OPTIONS", &cmd.options);
+    render_sub_cmds(&mut page, &sub_cmd.sub_cmds);
+    page.push_str(&format!(".SH SEE ALSO\n{}({})\n", bold(&cmd.name), SECTION));
+
+    Some(page)
+}
+
+fn collect_sub_paths(sub_cmds: &[SubCommand], prefix: &str, paths: &mut Vec<String>) {
+    for sub_cmd in sub_cmds.iter() {
+        let path = if prefix.is_empty() { sub_cmd.name.clone() } else { format!("{} {}", prefix, sub_cmd.name) };
+
+        paths.push(path.clone());
+        collect_sub_paths(&sub_cmd.sub_cmds, &path, paths);
+    }
+}
+
+/// Render man pages of command and all sub-commands (including nested sub-commands).
+/// Return (file name, content) of pages, e.g., `("git.1", ..)`, `("git-remote.1", ..)`, `("git-remote-add.1", ..)`.
+pub fn render_all(cmd: &Command) -> Vec<(String, String)> {
+    let mut pages = vec![(format!("{}.{}", cmd.name, SECTION), render(cmd))];
+    let mut paths = vec![];
+
+    collect_sub_paths(&cmd.sub_cmds, "", &mut paths);
+
+    for path in paths.iter() {
+        if let Some(page) = render_sub(cmd, path) {
+            pages.push((format!("{}-{}.{}", cmd.name, path.replace(' ', "-"), SECTION), page));
+        }
+    }
+
+    pages
+}
@@ -1,18 +1,29 @@
-use colored::Colorize;
-use std::ffi::OsString;
-use crate::Command;
-use crate::traits::{GetArgs, ValidateArgs, GetOpt};
-use crate::errors::{raise_error, UNKNOWN_OPT, UNKNOWN_SUB, INTERNAL_ERROR, MISMATCHED_ARGS};
-
+use std::ffi::{ OsStr, OsString };
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+use crate::{ Command, SubCommand, Options, OptionGroup, GroupKind, RepeatPolicy, NegativeNumbers };
+use crate::traits::{GetArgs, ValidateArgs, GetOpt, GetOpts, GetSubCmds};
+use crate::errors::{ ParseError, suggest };
+use crate::config;
+use crate::response;
+
+/// The last field of `Short`, `Long`, `Raw` and `Command` is the position in argv (the path of cli is at `0`),
+/// it's `None` if the segment isn't inputted, e.g., options read from environment variables.
+/// Errors of parsing are located by it.
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum Segment {
-    Short(String, Vec<Segment>),
-    Long(String, Vec<Segment>),
+    // short options of `-abc` are at the same position
+    Short(String, Vec<Segment>, Option<usize>),
+    Long(String, Vec<Segment>, Option<usize>),
     DoubleSub,
-    Raw(String),
+    // it may be invalid UTF-8, e.g., file names on Unix
+    Raw(OsString, Option<usize>),
     // if first element is None, arguments belong to command
     // if it's not, arguments belong to the only sub_command
-    Command(Option<String>, Vec<Segment>),
+    // the name of nested sub-command is its path separated by spaces, e.g., `remote add`
+    // the position is the one of the (innermost) sub-command, it's `None` for command
+    Command(Option<String>, Vec<Segment>, Option<usize>),
     None,
 }
 
@@ -23,14 +34,45 @@ impl Segment {
     /// 2. -short-opts
     /// 3. ------ // many -, but -- is valid
     /// 4. --long-option-
+    ///
+    /// Inputs which are invalid UTF-8 are always values, except values of `--long-option=value`.
+    /// Note, `args_os` doesn't contain the path of cli, so the position of `args_os[i]` is `i + 1`.
     pub fn from_vec(args_os: Vec<OsString>) -> Vec<Segment> {
+        Self::from_vec_by(args_os, |_| false)
+    }
+
+    /// Like `from_vec`, but inputs which look like negative numbers (e.g., `-5`, `-1.5`) are parsed
+    /// according to `cmd.negative_numbers`, see `NegativeNumbers`.
+    pub fn from_vec_for(cmd: &Command, args_os: Vec<OsString>) -> Vec<Segment> {
+        let mut shorts = vec![];
+
+        collect_shorts(cmd.get_opts(), &cmd.sub_cmds, &mut shorts);
+
+        Self::from_vec_by(args_os, |arg| match cmd.negative_numbers {
+            NegativeNumbers::Auto => !arg[1..].chars().all(|c| shorts.contains(&c.to_string())),
+            NegativeNumbers::Values => true,
+            NegativeNumbers::Options => false,
+        })
+    }
+
+    // `is_value` decides whether a negative number is a value or short options
+    fn from_vec_by<F: Fn(&str) -> bool>(args_os: Vec<OsString>, is_value: F) -> Vec<Segment> {
         let mut segments = vec![];
         let mut opts_end = false;
 
-        for arg_os in args_os.into_iter() {
-            let arg_os: String = arg_os
-                .into_string()
-                .expect("parse cli arguments failed, this is most likely caused by a character encoding problem");
+        for (idx, arg_os) in args_os.into_iter().enumerate() {
+            let at = Some(idx + 1);
+            let arg_os: String = match arg_os.into_string() {
+                Ok(arg) => arg,
+                Err(arg_os) => {
+                    match split_long_value(&arg_os) {
+                        Some((key, value)) if !opts_end => segments.push(Segment::Long(key, vec![Segment::Raw(value, at)], at)),
+                        _ => segments.push(Segment::Raw(arg_os, at)),
+                    }
+
+                    continue;
+                }
+            };
 
             if !opts_end {
                 if arg_os.starts_with("--") && !arg_os.starts_with("---") {
@@ -42,43 +84,46 @@ impl Segment {
                             segments.push(
                                 Segment::Long(
                                     key.to_string(),
-                                    vec![Segment::Raw(value.to_string())],
+                                    vec![Segment::Raw(value.into(), at)],
+                                    at,
                                 )
                             );
                         } else if arg_os[2..].split('-')
                             .collect::<Vec<&str>>()
                             .iter()
                             .all(|cs| Self::is_lit_word(cs)) {
-                            segments.push(Segment::Long(arg_os[2..].to_string(), vec![]));
+                            segments.push(Segment::Long(arg_os[2..].to_string(), vec![], at));
                         } else {
-                            segments.push(Segment::Raw(arg_os));
+                            segments.push(Segment::Raw(arg_os.into(), at));
                         }
                     } else if Self::is_double_sub(&arg_os) {
                         opts_end = true;
                         segments.push(Segment::DoubleSub)
                     } else {
-                        segments.push(Segment::Raw(arg_os.clone()));
+                        segments.push(Segment::Raw(arg_os.into(), at));
                     }
                 } else if arg_os.starts_with('-') {
-                    if Self::is_short(&arg_os) {
+                    if Self::is_negative_number(&arg_os) && is_value(&arg_os) {
+                        segments.push(Segment::Raw(arg_os.into(), at));
+                    } else if Self::is_short(&arg_os) {
                         let chrs: Vec<char> = arg_os[1..].chars().collect();
 
                         for chr in chrs {
                             let mut key = String::new();
 
                             key.push(chr);
-                            segments.push(Segment::Short(key, vec![]));
+                            segments.push(Segment::Short(key, vec![], at));
                         }
                     } else {
-                        segments.push(Segment::Raw(arg_os));
+                        segments.push(Segment::Raw(arg_os.into(), at));
                     }
                 } else if arg_os.is_empty() {
                     continue;
                 } else {
-                    segments.push(Segment::Raw(arg_os));
+                    segments.push(Segment::Raw(arg_os.into(), at));
                 }
             } else {
-                segments.push(Segment::Raw(arg_os));
+                segments.push(Segment::Raw(arg_os.into(), at));
             }
         }
 
@@ -125,6 +170,44 @@ impl Segment {
     pub fn is_double_sub(s: &str) -> bool {
         s == "--"
     }
+
+    // e.g., `-5`, `-1.5`, `-.5` and `-1e3`, but `-inf` and `-nan` are not
+    pub fn is_negative_number(s: &str) -> bool {
+        s.len() >= 2
+            && s.starts_with('-')
+            && s[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.')
+            && s[1..].parse::<f64>().is_ok()
+    }
+}
+
+// `--key=value` whose value is invalid UTF-8, the key must be valid
+#[cfg(unix)]
+fn split_long_value(arg: &OsStr) -> Option<(String, OsString)> {
+    use std::os::unix::ffi::{ OsStrExt, OsStringExt };
+
+    let bytes = arg.as_bytes();
+    let eq_idx = bytes.iter().position(|b| *b == b'=')?;
+    let key = std::str::from_utf8(&bytes[..eq_idx]).ok()?;
+
+    if Segment::is_long(&format!("{}=_", key)) {
+        Some((key[2..].to_string(), OsString::from_vec(bytes[(eq_idx + 1)..].to_vec())))
+    } else {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+fn split_long_value(_arg: &OsStr) -> Option<(String, OsString)> {
+    None
+}
+
+// short names of options of command and all sub-commands (including nested sub-commands)
+fn collect_shorts(options: &[Options], sub_cmds: &[SubCommand], shorts: &mut Vec<String>) {
+    shorts.extend(options.iter().filter_map(|opt| opt.short.clone()));
+
+    for sub_cmd in sub_cmds.iter() {
+        collect_shorts(sub_cmd.get_opts(), &sub_cmd.sub_cmds, shorts);
+    }
 }
 
 #[derive(Debug)]
@@ -133,7 +216,7 @@ pub enum TerminatorKind {
     GlobalVersion,
     Help(String),
     Version(String),
-    Other,
+    Error(ParseError),
 }
 
 #[derive(Debug)]
@@ -147,7 +230,40 @@ pub type InputCmdArgs = Option<Segment>;
 pub type InputSubArgs = Option<Segment>;
 pub type InputGlobalOpts = Vec<Segment>;
 pub type InputLocalOpts = Vec<Segment>;
-pub type ParserResult = Result<((InputCmdArgs, InputSubArgs), InputLocalOpts, InputGlobalOpts), TerminatorKind>;
+pub type ParsedSegments = ((InputCmdArgs, InputSubArgs), InputLocalOpts, InputGlobalOpts);
+pub type ParserResult = Result<ParsedSegments, TerminatorKind>;
+
+/// Where the value of an option comes from, see `Application::get_opt_source`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Source {
+    /// The option is offered in argv.
+    Cli,
+    /// The option is read from the environment variable.
+    Env(String),
+    /// The option is read from the config file, `key` is the dotted path, e.g., `install.global`.
+    Config { path: PathBuf, key: String },
+    /// Values are default values of arguments.
+    Default,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Cli => write!(f, "command line"),
+            Source::Env(env) => write!(f, "environment variable `{}`", env),
+            Source::Config { path, key } => write!(f, "`{}` of config file `{}`", key, path.display()),
+            Source::Default => write!(f, "default value"),
+        }
+    }
+}
+
+/// Sources of options which are not offered in argv, keyed by long names.
+/// Options offered in argv (or with default values) are not recorded.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OptionSources {
+    pub local: HashMap<String, Source>,
+    pub global: HashMap<String, Source>,
+}
 
 /// Note: Vec<Segment> doesn't contain the first element from `env::arg_os()`,
 /// it's usually the absolute path of cli, e.g., `/usr/bin/bash`.
@@ -160,26 +276,45 @@ impl SegmentWrapper {
         let mut raws = self.remove_raws();
 
         if !self.is_empty() {
-            if let Segment::Command(_, _) = &self.0[0] {
-                if let Segment::Command(name, mut args) = self.0.remove(0) {
+            if let Segment::Command(_, _, _) = &self.0[0] {
+                if let Segment::Command(name, mut args, at) = self.0.remove(0) {
                     if let Some(name) = name {
-                        args.push(Segment::Raw(name));
+                        args.push(Segment::Raw(name.into(), at));
                     }
 
                     let mut i = 0;
-                    let mut sub = None;
+                    let mut sub_path = vec![];
+                    // position of the innermost sub-command
+                    let mut sub_at = None;
                     let mut iter = args.iter();
 
-                    while let Some(Segment::Raw(raw_str)) = iter.next() {
-                        if cmd.get_sub_cmd(&raw_str).is_some() {
-                            sub = Some(raw_str.clone());
-                            args.remove(i);
+                    while let Some(Segment::Raw(raw_str, raw_at)) = iter.next() {
+                        // aliases are replaced by names of sub-commands
+                        if let Some(mut sub_cmd) = raw_str.to_str().and_then(|name| cmd.get_sub_cmd(name)) {
+                            sub_path.push(sub_cmd.name.clone());
+                            sub_at = *raw_at;
+
+                            // nested sub-commands follow their parent closely
+                            while let Some(Segment::Raw(raw_str, raw_at)) = iter.next() {
+                                if let Some(nested) = raw_str.to_str().and_then(|name| sub_cmd.get_sub_cmd(name)) {
+                                    sub_path.push(nested.name.clone());
+                                    sub_at = *raw_at;
+                                    sub_cmd = nested;
+                                } else {
+                                    break;
+                                }
+                            }
+
                             break;
                         }
 
                         i += 1;
                     }
 
+                    args.drain(i..(i + sub_path.len()));
+
+                    let sub = if sub_path.is_empty() { None } else { Some(sub_path.join(" ")) };
+
                     let mut cmd_args = vec![];
                     let mut sub_cmd_args = vec![];
                     let mut sub_name = String::new();
@@ -203,18 +338,18 @@ impl SegmentWrapper {
                     // 3. Command, None => command is offered but no sub-command
                     // None, None is impossible because that `args` will not be empty
                     return if cmd_args.is_empty() {
-                        (None, Some(Segment::Command(Some(sub_name), sub_cmd_args)))
+                        (None, Some(Segment::Command(Some(sub_name), sub_cmd_args, sub_at)))
                     } else if sub_cmd_args.is_empty() && sub_name.is_empty() {
-                        (Some(Segment::Command(None, cmd_args)), None)
+                        (Some(Segment::Command(None, cmd_args, None)), None)
                     } else {
-                        (Some(Segment::Command(None, cmd_args)), Some(Segment::Command(Some(sub_name), sub_cmd_args)))
+                        (Some(Segment::Command(None, cmd_args, None)), Some(Segment::Command(Some(sub_name), sub_cmd_args, sub_at)))
                     };
                 }
             } else if !raws.is_empty() {
-                return (Some(Segment::Command(None, raws)), None);
+                return (Some(Segment::Command(None, raws, None)), None);
             }
         } else if !raws.is_empty() {
-            return (Some(Segment::Command(None, raws)), None);
+            return (Some(Segment::Command(None, raws, None)), None);
         }
 
         (None, None)
@@ -226,7 +361,7 @@ impl SegmentWrapper {
 
         while i < self.len() {
             match &self.0[i] {
-                Segment::Long(_, _) | Segment::Short(_, _) => options.push(self.0.remove(i)),
+                Segment::Long(_, _, _) | Segment::Short(_, _, _) => options.push(self.0.remove(i)),
                 _ => i += 1,
             }
         }
@@ -240,14 +375,14 @@ impl SegmentWrapper {
 
         while i < self.len() {
             match &self.0[i] {
-                Segment::Short(name, _) => {
+                Segment::Short(name, _, _) => {
                     if cmd.get_short_opt(name).is_some() {
                         input_global_opts.push(self.0.remove(i));
                     } else {
                         i += 1;
                     }
                 }
-                Segment::Long(name, _) => {
+                Segment::Long(name, _, _) => {
                     if cmd.get_long_opt(name).is_some() {
                         input_global_opts.push(self.0.remove(i));
                     } else {
@@ -275,8 +410,8 @@ impl SegmentWrapper {
         }
 
         for _ in i..len {
-            if let Segment::Raw(raw_s) = self.0.remove(i) {
-                raws.push(Segment::Raw(raw_s));
+            if let raw @ Segment::Raw(_, _) = self.0.remove(i) {
+                raws.push(raw);
             }
         }
 
@@ -292,7 +427,7 @@ impl SegmentWrapper {
 
         while left <= right && right < self.len() {
             match &mut self.0[right] {
-                Segment::Short(_, _) | Segment::Long(_, _) => {
+                Segment::Short(_, _, _) | Segment::Long(_, _, _) => {
                     left = right;
                     right += 1;
                 }
@@ -307,7 +442,7 @@ impl SegmentWrapper {
                     } else {
                         let r = self.0.remove(right);
                         match &mut self.0[left] {
-                            Segment::Short(_, args) | Segment::Long(_, args) => args.push(r),
+                            Segment::Short(_, args, _) | Segment::Long(_, args, _) => args.push(r),
                             _ => continue,
                         }
                     }
@@ -327,7 +462,7 @@ impl SegmentWrapper {
             // stop once encounter a short option or long option
             for i in 0..self.len() {
                 match &self.0[i] {
-                    Segment::Short(_, _) | Segment::Long(_, _) | Segment::DoubleSub => {
+                    Segment::Short(_, _, _) | Segment::Long(_, _, _) | Segment::DoubleSub => {
                         end = i;
                         break;
                     }
@@ -335,7 +470,7 @@ impl SegmentWrapper {
                 }
             }
 
-            if let Segment::Command(_, _) = &mut self.0[0] {
+            if let Segment::Command(_, _, _) = &mut self.0[0] {
                 start = 1;
             }
 
@@ -344,47 +479,76 @@ impl SegmentWrapper {
             }
 
             if start == 0 && !cmd_args.is_empty() {
-                self.0.insert(0, Segment::Command(None, cmd_args));
-            } else if let Segment::Command(_, args) = &mut self.0[0] {
+                self.0.insert(0, Segment::Command(None, cmd_args, None));
+            } else if let Segment::Command(_, args, _) = &mut self.0[0] {
                 args.append(&mut cmd_args);
             }
         }
     }
 
-    fn check_options<T: GetOpt>(options: &[Segment], cmd: &T) -> Result<(), String> {
-        let mut all = true;
-        let mut error = String::new();
+    // `candidates` are options which could be offered, they are used to suggest if an option is unknown
+    fn check_options<T: GetOpt>(options: &[Segment], cmd: &T, candidates: &[&Options]) -> Result<(), ParseError> {
+        // long names of options offered, used to find repeated options
+        let mut offered = HashSet::new();
 
         for opt in options {
-            match opt {
-                Segment::Short(name, _) => {
-                    if cmd.get_short_opt(name).is_none() {
-                        all = false;
-                        error = format!("{} `{}`", UNKNOWN_OPT, format!("-{}", name).bold());
-                        break;
+            let (def, token, index) = match opt {
+                Segment::Short(name, _, at) => (cmd.get_short_opt(name), format!("-{}", name), *at),
+                Segment::Long(name, _, at) => (cmd.get_long_opt(name), format!("--{}", name), *at),
+                _ => continue,
+            };
+
+            match def {
+                Some(def) => {
+                    // the repeated option is the later occurrence
+                    if !offered.insert(def.long.as_str()) && def.repeat == RepeatPolicy::Error {
+                        return Err(ParseError::RepeatedOption { token, index });
                     }
                 }
-                Segment::Long(name, _) => {
-                    if cmd.get_long_opt(name).is_none() {
-                        all = false;
-                        error = format!("{} `{}`", UNKNOWN_OPT, format!("--{}", name).bold());
-                        break;
-                    }
+                None => return Err(Self::unknown_option(opt, candidates)),
+            }
+        }
+
+        Ok(())
+    }
+
+    // only long options are suggested, because short options are always similar to each other
+    fn unknown_option(opt: &Segment, candidates: &[&Options]) -> ParseError {
+        match opt {
+            Segment::Short(name, _, at) => ParseError::UnknownOption { token: format!("-{}", name), suggestion: None, index: *at },
+            Segment::Long(name, _, at) => ParseError::UnknownOption {
+                token: format!("--{}", name),
+                suggestion: suggest(name, candidates.iter().map(|opt| opt.long.as_str())).map(|long| format!("--{}", long)),
+                index: *at,
+            },
+            _ => ParseError::Internal(format!("{} {}", file!(), line!())),
+        }
+    }
+
+    // if arguments are mismatched, the first argument may be a misspelled sub-command, e.g., `instal`
+    // it's regarded as an unknown sub-command if a similar sub-command exists
+    fn check_misspelled_sub_cmd<T: GetArgs>(segs: &Segment, def: &T, sub_cmds: &[SubCommand]) -> Result<(), ParseError> {
+        if let Segment::Command(_, args, _) = segs {
+            if let (Some(Segment::Raw(first, at)), Err(_)) = (args.first(), Self::check_arguments(segs, def, "")) {
+                let suggestion = suggest(&first.to_string_lossy(), sub_cmds.iter().map(|sub_cmd| sub_cmd.name.as_str()));
+
+                if suggestion.is_some() {
+                    return Err(ParseError::UnknownSubCommand { token: first.to_string_lossy().to_string(), suggestion, index: *at });
                 }
-                _ => continue,
             }
         }
 
-        if all { Ok(()) } else { Err(error) }
+        Ok(())
     }
 
     // check arguments of command or sub-command, option
-    fn check_arguments<T: GetArgs>(target: &Segment, source: &T) -> Result<(), String> {
+    // `cmd_name` is only used to report errors of command
+    pub(crate) fn check_arguments<T: GetArgs>(target: &Segment, source: &T, cmd_name: &str) -> Result<(), ParseError> {
         // if it's false, panic is necessary because it will not work at all
         // In theory it will never be false, because you can’t construct an invalid arguments group
         // but who can ensure anything in the world?
         if !source.validate_args() {
-            return Err(format!("{} Position: {} {}", INTERNAL_ERROR, file!(), line!()));
+            return Err(ParseError::Internal(format!("{} {}", file!(), line!())));
         }
 
         let (mut min, mut max) = (0, 0);
@@ -406,111 +570,327 @@ impl SegmentWrapper {
                 max = usize::max_value();
             }
         }
-        let mut def_args_fmt = String::new();
-        let mut input_args_fmt = String::new();
-        let mut error = false;
 
         match target {
-            Segment::Short(_, args)
-            | Segment::Long(_, args)
-            | Segment::Command(_, args) => {
-                if args.len() < min || args.len() > max {
-                    def_args_fmt = {
-                        let tmp = source.get_args();
-                        let mut str = String::new();
-
-                        for arg in tmp.iter() {
-                            str = format!("{}{} ", str, arg);
-                        }
+            Segment::Short(_, args, _)
+            | Segment::Long(_, args, _)
+            | Segment::Command(_, args, _) => {
+                if args.len() >= min && args.len() <= max {
+                    return Ok(());
+                }
 
-                        str.trim().to_string()
-                    };
-                    input_args_fmt = {
-                        let mut str = String::new();
-
-                        for arg in args.iter() {
-                            let tmp = if let Segment::Raw(str) = arg { str.as_str() } else { "" };
-                            if str.is_empty() {
-                                str = tmp.to_string();
-                            } else {
-                                str = format!("{}, {}", str, tmp);
-                            }
-                        }
+                let expected = source
+                    .get_args()
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                let inputs: Vec<String> = args
+                    .iter()
+                    .map(|arg| if let Segment::Raw(str, _) = arg { str.to_string_lossy().to_string() } else { String::new() })
+                    .collect();
+
+                // three conditions, beautificate the error
+                // 1. arguments of option are mismatched
+                // 2. lack of input arguments
+                // 3. extra input arguments
+                match target {
+                    Segment::Short(name, _, at) => Err(ParseError::BadOptionArgument {
+                        token: format!("-{}", name),
+                        expected,
+                        inputs,
+                        index: *at,
+                    }),
+                    Segment::Long(name, _, at) => Err(ParseError::BadOptionArgument {
+                        token: format!("--{}", name),
+                        expected,
+                        inputs,
+                        index: *at,
+                    }),
+                    Segment::Command(name, _, at) => if args.len() < min {
+                        // the path of cli is always at 0
+                        let (token, index) = match name {
+                            Some(name) => (name.to_string(), *at),
+                            None => (cmd_name.to_string(), Some(0)),
+                        };
+
+                        Err(ParseError::MissingArgument { token, expected, index })
+                    } else {
+                        let index = if let Segment::Raw(_, at) = &args[max] { *at } else { None };
 
-                        str.trim().to_string()
-                    };
-                    error = true;
+                        Err(ParseError::TooManyArguments { token: inputs[max].clone(), expected, index })
+                    },
+                    _ => Ok(()),
                 }
             }
-            _ => {}
+            _ => Ok(()),
         }
+    }
 
-        if error {
-            let name = match target {
-                Segment::Short(name, _) => format!("`{}`", format!("-{}", name).bold()),
-                Segment::Long(name, _) => format!("`{}`", format!("--{}", name).bold()),
-                Segment::Command(name, _) => {
-                    if let Some(name) = name {
-                        format!("sub-command `{}`", name.bold())
-                    } else {
-                        "command".to_string()
+    fn check_gol_option_arguments<T: GetOpt>(ins: &T, options: &[Segment]) -> Result<(), ParseError> {
+        for opt in options.iter() {
+            match opt {
+                Segment::Long(name, _, _) => {
+                    if let Some(def_opt) = ins.get_long_opt(name) {
+                        Self::check_arguments(opt, def_opt, "")?;
                     }
                 }
-                _ => "which".to_string(),
+                Segment::Short(name, _, _) => {
+                    if let Some(def_opt) = ins.get_short_opt(name) {
+                        Self::check_arguments(opt, def_opt, "")?;
+                    }
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(())
+    }
+
+    // options which are not offered but whose environment variables are set, e.g., `env = "APP_TOKEN"`
+    // the value is split by whitespaces if the option accepts more than one argument
+    // an empty value is regarded as unset, and `0` or `false` means that a flag is not offered
+    // sources of them are recorded in `sources`
+    fn env_options<T: GetOpts>(def: &T, offered: &[Segment], sources: &mut HashMap<String, Source>) -> Vec<Segment> {
+        let mut env_options = vec![];
+
+        for opt in def.get_opts().iter() {
+            let (env, value) = match &opt.env {
+                Some(env) if !opt.is_offered(offered) => (env, std::env::var(env).unwrap_or_default()),
+                _ => continue,
             };
+            let args = opt.get_args();
+
+            if value.is_empty() {
+                continue;
+            }
 
-            // two conditions, beautificate the error text
-            // 1. lack of input arguments
-            // 2. extra input arguments
-            return if input_args_fmt.is_empty() {
-                Err(format!(
-                    "{} Arguments of {} are defined as `{}`, but you input nothing.",
-                    MISMATCHED_ARGS, name, def_args_fmt.bold(),
-                ))
-            } else if def_args_fmt.is_empty() {
-                Err(format!(
-                    "{} {} doesn't accept any argument, but you input `{}`.",
-                    MISMATCHED_ARGS, name, input_args_fmt.bold(),
-                ))
+            let seg = if args.is_empty() {
+                if value != "0" && value.to_lowercase() != "false" {
+                    Segment::Long(opt.long.clone(), vec![], None)
+                } else if opt.negatable {
+                    Segment::Long(format!("no-{}", opt.long), vec![], None)
+                } else {
+                    // the flag is off, it's regarded as not offered
+                    continue;
+                }
+            } else if args.len() == 1 && !args[0].ty.is_multiply() {
+                Segment::Long(opt.long.clone(), vec![Segment::Raw(value.into(), None)], None)
             } else {
-                Err(format!(
-                    "{} Arguments of {} are defined as `{}`, but you input `{}`.",
-                    MISMATCHED_ARGS, name, def_args_fmt.bold(), input_args_fmt.bold(),
-                ))
+                let values = value.split_whitespace().map(|v| Segment::Raw(v.into(), None)).collect();
+
+                Segment::Long(opt.long.clone(), values, None)
             };
+
+            sources.insert(opt.long.clone(), Source::Env(env.clone()));
+            env_options.push(seg);
+        }
+
+        env_options
+    }
+
+    // options which are not offered (or read from environment variables) but are in the config file
+    // `table` is the table of command or sub-command, `prefix` is its dotted path, e.g., `remote.add`
+    fn config_options<T: GetOpts>(
+        def: &T,
+        offered: &[Segment],
+        (path, table, prefix): (&PathBuf, &config::ConfigValue, &str),
+        sources: &mut HashMap<String, Source>,
+    ) -> Result<Vec<Segment>, ParseError> {
+        let mut config_options = vec![];
+
+        for (seg, long, key) in config::options(path, def, table, prefix, offered)? {
+            sources.insert(long, Source::Config { path: path.clone(), key });
+            config_options.push(seg);
+        }
+
+        Ok(config_options)
+    }
+
+    // `name` is the name of command or the path of sub-command, it's shown in the error
+    fn check_required_options<T: GetOpts>(def: &T, offered: &[Segment], name: &str) -> Result<(), ParseError> {
+        for opt in def.get_opts().iter().filter(|opt| opt.required) {
+            // the negation of a negatable flag is offered as well
+            let is_offered = offered.iter().any(|seg| match seg {
+                Segment::Long(long, _, _) => long == &opt.long || opt.is_negated(long),
+                Segment::Short(short, _, _) => Some(short) == opt.short.as_ref(),
+                _ => false,
+            });
+
+            if !is_offered {
+                return Err(ParseError::MissingRequiredOption {
+                    token: format!("--{}", opt.long),
+                    cmd: name.to_string(),
+                    index: None,
+                });
+            }
         }
 
         Ok(())
     }
 
-    fn check_gol_option_arguments<T: GetOpt>(ins: &T, options: &[Segment]) -> Result<(), String> {
-        for opt in options.iter() {
-            match opt {
-                Segment::Long(name, _) => {
-                    if let Some(def_opt) = ins.get_long_opt(name) {
-                        if let Err(err) = Self::check_arguments(opt, def_opt) {
-                            return Err(err);
-                        }
-                    }
+    // names of arguments which are offered, i.e., the first `n` arguments if `n` arguments are inputted
+    fn offered_arguments<T: GetArgs>(def: &T, segs: &Segment) -> Vec<String> {
+        let count = if let Segment::Command(_, args, _) = segs { args.len() } else { 0 };
+
+        def.get_args().iter().take(count).map(|arg| arg.name.clone()).collect()
+    }
+
+    // check relationships of options, i.e., `conflicts_with`, `requires`, `required_unless` and groups
+    // `defs` are options of the command and the sub-command offered, `args` are names of arguments offered
+    fn check_constraints(defs: &[&Options], offered: &[&Segment], args: &[String]) -> Result<(), ParseError> {
+        // long names of options offered in order, how they are offered (e.g., `-s` or `--long`) and their positions
+        // negations are not regarded as offered
+        let mut offered_longs: Vec<(&str, String, Option<usize>)> = vec![];
+
+        for seg in offered {
+            let (def, token, at) = match seg {
+                Segment::Long(long, _, at) => (defs.iter().find(|opt| &opt.long == long), format!("--{}", long), *at),
+                Segment::Short(short, _, at) => (defs.iter().find(|opt| opt.short.as_ref() == Some(short)), format!("-{}", short), *at),
+                _ => continue,
+            };
+
+            if let Some(def) = def {
+                if !offered_longs.iter().any(|(long, _, _)| long == &def.long) {
+                    offered_longs.push((&def.long, token, at));
                 }
-                Segment::Short(name, _) => {
-                    if let Some(def_opt) = ins.get_short_opt(name) {
-                        if let Err(err) = Self::check_arguments(opt, def_opt) {
-                            return Err(err);
-                        }
-                    }
+            }
+        }
+
+        let offered_token = |name: &str| offered_longs
+            .iter()
+            .find(|(long, _, _)| long == &name)
+            .map(|(_, token, at)| (token.clone(), *at));
+        let is_offered = |name: &str| offered_token(name).is_some() || args.iter().any(|arg| arg == name);
+        // options are shown as `--long`, arguments are shown as their names
+        let fmt_name = |name: &str| if defs.iter().any(|opt| opt.long == name) {
+            format!("--{}", name)
+        } else {
+            name.to_string()
+        };
+
+        for opt in defs {
+            if let Some((token, index)) = offered_token(&opt.long) {
+                if let Some(conflict) = opt.conflicts_with.iter().find(|name| is_offered(name)) {
+                    return Err(ParseError::ConflictingOptions { token, conflict: fmt_name(conflict), index });
                 }
+
+                if let Some(required) = opt.requires.iter().find(|name| !is_offered(name)) {
+                    return Err(ParseError::MissingDependency { token, required: fmt_name(required), index });
+                }
+            } else if !opt.required_unless.is_empty() && !opt.required_unless.iter().any(|name| is_offered(name)) {
+                return Err(ParseError::MissingConditionalOption {
+                    token: format!("--{}", opt.long),
+                    unless: opt.required_unless.iter().map(|name| fmt_name(name)).collect(),
+                    index: None,
+                });
+            }
+        }
+
+        // groups in order of definition, the kind of a group is decided by its first option
+        let mut groups: Vec<&OptionGroup> = vec![];
+
+        for group in defs.iter().filter_map(|opt| opt.group.as_ref()) {
+            if !groups.iter().any(|g| g.name == group.name) {
+                groups.push(group);
+            }
+        }
+
+        for group in groups {
+            let members: Vec<&str> = defs
+                .iter()
+                .filter(|opt| opt.group.as_ref().map(|g| &g.name) == Some(&group.name))
+                .map(|opt| opt.long.as_str())
+                .collect();
+            let offered_members: Vec<(&String, Option<usize>)> = offered_longs
+                .iter()
+                .filter(|(long, _, _)| members.contains(long))
+                .map(|(_, token, at)| (token, *at))
+                .collect();
+            let (token, index) = match (group.kind, offered_members.as_slice()) {
+                (_, []) => (String::new(), None),
+                (GroupKind::ExactlyOne, [_, (redundant, at), ..]) => (redundant.to_string(), *at),
                 _ => continue,
+            };
+
+            return Err(ParseError::GroupViolation {
+                token,
+                group: group.name.clone(),
+                members: members.iter().map(|long| format!("--{}", long)).collect(),
+                index,
+            });
+        }
+
+        Ok(())
+    }
+
+    // options not offered fall back to their environment variables, then the config file
+    // the path of config file may be read from the environment variable as well
+    fn layer_options(
+        cmd: &Command,
+        sub_path: Option<&str>,
+        local_options: &mut Vec<Segment>,
+        global_options: &mut Vec<Segment>,
+        sources: &mut OptionSources,
+    ) -> Result<(), ParseError> {
+        let sub_cmd = sub_path.and_then(|sub_path| cmd.find_sub_cmd(sub_path));
+
+        if let Some(sub_cmd) = sub_cmd {
+            let mut env_options = Self::env_options(sub_cmd, local_options, &mut sources.local);
+
+            local_options.append(&mut env_options);
+        }
+
+        let mut env_options = Self::env_options(cmd, global_options, &mut sources.global);
+
+        global_options.append(&mut env_options);
+
+        // errors of the config file are located at its path in argv
+        let (path, table, at) = match config::load_for(cmd, global_options)? {
+            Some(loaded) => loaded,
+            None => return Ok(()),
+        };
+
+        config::validate(&path, cmd, &table, "").map_err(|err| err.with_index(at))?;
+
+        let mut config_options = Self::config_options(cmd, global_options, (&path, &table, ""), &mut sources.global)
+            .map_err(|err| err.with_index(at))?;
+
+        global_options.append(&mut config_options);
+
+        // the table of nested sub-command is nested as well, e.g., `[remote.add]`
+        if let (Some(sub_cmd), Some(sub_path)) = (sub_cmd, sub_path) {
+            let mut sub_table = Some(&table);
+            let mut sub_cmds = cmd.get_sub_cmds();
+            let mut names = vec![];
+
+            // aliases may be offered, but tables are named after names
+            for name in sub_path.split(' ') {
+                if let Some(sub) = sub_cmds.iter().find(|sub| sub.is_named(name)) {
+                    sub_table = sub_table.and_then(|table| table.get(&sub.name));
+                    sub_cmds = sub.get_sub_cmds();
+                    names.push(sub.name.as_str());
+                }
+            }
+
+            if let Some(sub_table) = sub_table {
+                let prefix = names.join(".");
+                let mut config_options = Self::config_options(sub_cmd, local_options, (&path, sub_table, &prefix), &mut sources.local)
+                    .map_err(|err| err.with_index(at))?;
+
+                local_options.append(&mut config_options);
             }
         }
 
         Ok(())
     }
 
-    fn parse(&mut self, cmd: &Command) -> ParserResult {
-        let mut success = true;
+    fn parse(&mut self, cmd: &Command, sources: &mut OptionSources) -> ParserResult {
+        self.parse_segments(cmd, sources).map_err(TerminatorKind::Error)
+    }
 
-        let tmp = if !self.0.is_empty() {
+    fn parse_segments(&mut self, cmd: &Command, sources: &mut OptionSources) -> Result<ParsedSegments, ParseError> {
+        if !self.0.is_empty() {
             // if self.0 is non-empty, do operations below
             // because some operations need to index at 0 which may raise errors
 
@@ -523,117 +903,148 @@ impl SegmentWrapper {
             self.divide_cmd_arguments();
 
             let (cmd_segs, sub_segs) = self.remove_cmd(cmd);
-            let global_options = self.remove_global_options(cmd);
-            let local_options = self.remove_options();
+            let mut global_options = self.remove_global_options(cmd);
+            let mut local_options = self.remove_options();
 
             // begin to validate input arguments, two steps
 
             // step-1: validate that all options are valid(if it's registered then it's valid)
 
+            // step-0: check whether a sub-command is misspelled, e.g., `instal` instead of `install`
+            // misspelled sub-command is regarded as arguments, so it's found if arguments are mismatched
+            match (&cmd_segs, &sub_segs) {
+                (Some(cmd_segs), None) => Self::check_misspelled_sub_cmd(cmd_segs, cmd, &cmd.sub_cmds)?,
+                (_, Some(sub_segs @ Segment::Command(Some(sub_cmd_name), _, _))) => {
+                    if let Some(sub_cmd) = cmd.find_sub_cmd(sub_cmd_name) {
+                        Self::check_misspelled_sub_cmd(sub_segs, sub_cmd, &sub_cmd.sub_cmds)?;
+                    }
+                }
+                _ => {}
+            }
+
+            let global_candidates: Vec<&Options> = cmd.options.iter().collect();
+
             // validate local options and global options respectively
             // global options is always valid, because it's construct through the `Command` instance
             // u can assume `Command` is valid
-            if let Some(sub_segs) = &sub_segs {
-                if let Segment::Command(Some(sub_cmd_name), _) = sub_segs {
-                    if let Some(sub_cmd) = cmd.get_sub_cmd(&sub_cmd_name) {
-                        // if the sub-command offered is one of the sub-commands registered
-                        // try to check whether all local-options belong to the sub-command offered or not
-                        if let Err(err) = Self::check_options(&local_options, sub_cmd) {
-                            success = raise_error(err);
-                        }
-                    } else {
-                        // unreachable branch
-                        success = raise_error(format!("{} `{}`", UNKNOWN_SUB, sub_cmd_name.bold()));
-                    }
+            if let Some(Segment::Command(Some(sub_cmd_name), _, _)) = &sub_segs {
+                if let Some(sub_cmd) = cmd.find_sub_cmd(sub_cmd_name) {
+                    let candidates: Vec<&Options> = sub_cmd.options.iter().chain(cmd.options.iter()).collect();
+
+                    // if the sub-command offered is one of the sub-commands registered
+                    // try to check whether all local-options belong to the sub-command offered or not
+                    Self::check_options(&local_options, sub_cmd, &candidates)?;
+                } else {
+                    // unreachable branch
+                    return Err(ParseError::UnknownSubCommand { token: sub_cmd_name.to_string(), suggestion: None, index: None });
                 }
-            } else {
+            } else if let Some(opt) = local_options.first() {
                 // if no sub-command is offered but the local options are not empty
                 // it means that these local options are unknown
-                if let Some(local_opt) = local_options.get(0) {
-                    if let Segment::Short(name, _) = local_opt {
-                        success = raise_error(format!("{} `{}`", UNKNOWN_OPT, format!("-{}", name).bold()));
-                    } else if let Segment::Long(name, _) = local_opt {
-                        success = raise_error(format!("{} `{}`", UNKNOWN_OPT, format!("--{}", name).bold()));
-                    }
-                }
+                return Err(Self::unknown_option(opt, &global_candidates));
             }
 
             // check global options
             // In fact, this step will never raise error
             // because that `global_option` is parsed from `cmd`, so they are compatible
-            if let Err(err) = Self::check_options(&global_options, cmd) {
-                success = raise_error(err);
+            Self::check_options(&global_options, cmd, &global_candidates)?;
+
+            // options not offered fall back to their environment variables, then the config file
+            let sub_path = if let Some(Segment::Command(Some(sub_cmd_name), _, _)) = &sub_segs {
+                Some(sub_cmd_name.as_str())
+            } else { None };
+
+            Self::layer_options(cmd, sub_path, &mut local_options, &mut global_options, sources)?;
+
+            // check whether required options are offered (or read from environment variables or the config file)
+            Self::check_required_options(cmd, &global_options, &cmd.name)?;
+
+            if let Some(Segment::Command(Some(sub_cmd_name), _, _)) = &sub_segs {
+                if let Some(sub_cmd) = cmd.find_sub_cmd(sub_cmd_name) {
+                    Self::check_required_options(sub_cmd, &local_options, sub_cmd_name)?;
+                }
             }
 
             // step-2: validate that if arguments defined and arguments inputted are equivalent
 
             // check arguments of command if it offered
-            if let Some(cmd_segs) = &cmd_segs {
-                if let Segment::Command(none, _) = cmd_segs {
-                    // command shouldn't have a name
-                    if none.is_none() {
-                        if let Err(err) = Self::check_arguments(cmd_segs, cmd) {
-                            success = raise_error(err);
-                        }
-                    }
-                }
+            // command shouldn't have a name
+            if let Some(cmd_segs @ Segment::Command(None, _, _)) = &cmd_segs {
+                Self::check_arguments(cmd_segs, cmd, &cmd.name)?;
             }
 
             // check arguments of sub-command if it offered
-            if let Some(sub_segs) = &sub_segs {
-                if let Segment::Command(sub_name, _) = sub_segs {
-                    if let Some(sub_name) = sub_name {
-                        if let Some(sub_cmd) = cmd.get_sub_cmd(sub_name) {
-                            if let Err(err) = Self::check_arguments(sub_segs, sub_cmd) {
-                                success = raise_error(err);
-                            }
+            if let Some(sub_segs @ Segment::Command(Some(sub_name), _, _)) = &sub_segs {
+                if let Some(sub_cmd) = cmd.find_sub_cmd(sub_name) {
+                    Self::check_arguments(sub_segs, sub_cmd, &cmd.name)?;
 
-                            // check arguments of local options of specific sub-command
-                            if let Err(err) = Self::check_gol_option_arguments(sub_cmd, &local_options) {
-                                success = raise_error(err);
-                            }
-                        }
-                    }
+                    // check arguments of local options of specific sub-command
+                    Self::check_gol_option_arguments(sub_cmd, &local_options)?;
                 }
             }
 
             // check arguments of global options
-            if let Err(err) = Self::check_gol_option_arguments(cmd, &global_options) {
-                success = raise_error(err);
+            Self::check_gol_option_arguments(cmd, &global_options)?;
+
+            // check relationships of options and arguments offered
+            let mut defs: Vec<&Options> = cmd.options.iter().collect();
+            let mut args = vec![];
+
+            if let Some(cmd_segs @ Segment::Command(None, _, _)) = &cmd_segs {
+                args.append(&mut Self::offered_arguments(cmd, cmd_segs));
+            }
+
+            if let Some(sub_segs @ Segment::Command(Some(sub_name), _, _)) = &sub_segs {
+                if let Some(sub_cmd) = cmd.find_sub_cmd(sub_name) {
+                    defs.extend(sub_cmd.options.iter());
+                    args.append(&mut Self::offered_arguments(sub_cmd, sub_segs));
+                }
             }
 
-            ((cmd_segs, sub_segs), local_options, global_options)
+            let offered: Vec<&Segment> = local_options.iter().chain(global_options.iter()).collect();
+
+            Self::check_constraints(&defs, &offered, &args)?;
+
+            Ok(((cmd_segs, sub_segs), local_options, global_options))
         } else {
             // check if user doesn't input any arguments (sel.0 is empty)
             // but at this situation, command might accept arguments
             // if do not do any checking, it will raise some `Rust` runtime errors which are difficult to understand and debug
             // so do checking through constructing an empty `Segment::Command`
-            if let Err(err) = Self::check_arguments(&Segment::Command(None, vec![]), cmd) {
-                success = raise_error(err);
-            }
+            Self::check_arguments(&Segment::Command(None, vec![], None), cmd, &cmd.name)?;
 
-            ((None, None), vec![], vec![])
-        };
+            let mut global_options = vec![];
+
+            Self::layer_options(cmd, None, &mut vec![], &mut global_options, sources)?;
+
+            Self::check_required_options(cmd, &global_options, &cmd.name)?;
+            Self::check_gol_option_arguments(cmd, &global_options)?;
+
+            let defs: Vec<&Options> = cmd.options.iter().collect();
+            let offered: Vec<&Segment> = global_options.iter().collect();
 
-        if success { Ok(tmp) } else { Err(TerminatorKind::Other) }
+            Self::check_constraints(&defs, &offered, &[])?;
+
+            Ok(((None, None), vec![], global_options))
+        }
     }
 
     #[cfg(feature = "test")]
     pub fn parse_test(&mut self, cmd: &Command) -> ParserResult {
-        self.parse(cmd)
+        self.parse(cmd, &mut OptionSources::default())
     }
 
     pub fn get_terminator(&self) -> TerminatorType {
         for seg in self.0.iter() {
             match seg {
-                Segment::Short(name, _) => {
+                Segment::Short(name, _, _) => {
                     if name == "v" {
                         return TerminatorType::Version;
                     } else if name == "h" {
                         return TerminatorType::Help;
                     }
                 }
-                Segment::Long(name, _) => {
+                Segment::Long(name, _, _) => {
                     if name == "version" {
                         return TerminatorType::Version;
                     } else if name == "help" {
@@ -649,16 +1060,61 @@ impl SegmentWrapper {
 
     // return value is only using for testing
     pub fn parse_cli(cmd: &Command) -> ParserResult {
-        // first element is useless.collect();
-        let args_os: Vec<OsString> = std::env::args_os().skip(1).collect();
-        let segments = Segment::from_vec(args_os);
+        Self::parse_from(cmd, std::env::args_os())
+    }
+
+    /// Like `parse_cli`, but parse the specified argv instead of `std::env::args_os()`.
+    /// Note, the first element of `args` should be the path of cli (the same as `std::env::args_os()`), it will be ignored.
+    /// If `cmd.response_files` is `true`, `@file` is expanded into arguments read from the file (see `response`).
+    pub fn parse_from<I, T>(cmd: &Command, args: I) -> ParserResult
+        where I: IntoIterator<Item = T>, T: Into<OsString> {
+        Self::parse_with_sources(cmd, args).0
+    }
+
+    /// Like `parse_from`, but return sources of options which are not offered in argv as well,
+    /// e.g., options read from environment variables or the config file (see `Application::with_sources`).
+    pub fn parse_with_sources<I, T>(cmd: &Command, args: I) -> (ParserResult, OptionSources)
+        where I: IntoIterator<Item = T>, T: Into<OsString> {
+        let mut argv: Vec<OsString> = args.into_iter().map(Into::into).collect();
+
+        Self::parse_argv(cmd, &mut argv)
+    }
+
+    /// Like `parse_with_sources`, but `argv` is replaced by the expanded one if response files are expanded,
+    /// so that errors raised after parsing (e.g., conversion errors) can be located in it (see `ParseError::locate`).
+    pub fn parse_argv(cmd: &Command, argv: &mut Vec<OsString>) -> (ParserResult, OptionSources) {
+        // expand response files before anything else, then errors are located in the expanded argv
+        if cmd.response_files {
+            match response::expand(argv.clone()) {
+                Ok(expanded) => *argv = expanded,
+                Err(err) => return (Err(TerminatorKind::Error(err)), OptionSources::default()),
+            }
+        }
+
+        // first element is useless
+        let segments = Segment::from_vec_for(cmd, argv.iter().skip(1).cloned().collect());
         let mut segment_wrapper = SegmentWrapper(segments);
         let terminator = segment_wrapper.get_terminator();
-        let first_sub = if segment_wrapper.is_empty() {
-            None
-        } else if let Segment::Raw(may_sub_name) = &segment_wrapper.0[0] {
-            cmd.get_sub_cmd(may_sub_name)
-        } else { None };
+        // the sub-command (maybe nested) which is offered at the beginning
+        let mut first_sub: Option<&SubCommand> = None;
+        let mut sub_path = vec![];
+
+        for seg in segment_wrapper.0.iter() {
+            if let Segment::Raw(may_sub_name, _) = seg {
+                let may_sub = match first_sub {
+                    Some(sub) => may_sub_name.to_str().and_then(|name| sub.get_sub_cmd(name)),
+                    None => may_sub_name.to_str().and_then(|name| cmd.get_sub_cmd(name)),
+                };
+
+                if let Some(sub) = may_sub {
+                    first_sub = may_sub;
+                    sub_path.push(sub.name.as_str());
+                    continue;
+                }
+            }
+
+            break;
+        }
 
         // if any terminator inputted and defined (e.g., v, version, h, help)
         // it will not parse, because they have special callbacks
@@ -666,29 +1122,32 @@ impl SegmentWrapper {
             TerminatorType::Help => {
                 if let Some(sub) = first_sub {
                     if sub.get_long_opt("help").is_some() {
-                        return Err(TerminatorKind::Help(sub.name.clone()));
+                        return (Err(TerminatorKind::Help(sub_path.join(" "))), OptionSources::default());
                     }
                 }
 
                 if cmd.get_long_opt("help").is_some() {
-                    return Err(TerminatorKind::GlobalHelp);
+                    return (Err(TerminatorKind::GlobalHelp), OptionSources::default());
                 }
             }
             TerminatorType::Version => {
                 if let Some(sub) = first_sub {
                     if sub.get_long_opt("version").is_some() {
-                        return Err(TerminatorKind::Version(sub.name.clone()));
+                        return (Err(TerminatorKind::Version(sub_path.join(" "))), OptionSources::default());
                     }
                 }
 
                 if cmd.get_long_opt("version").is_some() {
-                    return Err(TerminatorKind::GlobalVersion);
+                    return (Err(TerminatorKind::GlobalVersion), OptionSources::default());
                 }
             }
             TerminatorType::None => {}
         }
 
-        segment_wrapper.parse(cmd)
+        let mut sources = OptionSources::default();
+        let result = segment_wrapper.parse(cmd, &mut sources);
+
+        (result, sources)
     }
 
     pub fn len(&self) -> usize {
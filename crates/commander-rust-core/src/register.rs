@@ -0,0 +1,234 @@
+//! Registrations of `#[command]` and `#[sub_command]`, which are used by `execute!`.
+//!
+//! Each `#[command]` or `#[sub_command]` generates a `const fn` returning its `CmdInfo` or `SubInfo`,
+//! `execute!` checks them by `check` in a const context (so errors are reported when compiling),
+//! then builds the command and dispatchers of sub-commands by `assemble`.
+
+use crate::{ Command, SubCommand };
+use crate::converters::Application;
+use crate::errors::ParseError;
+use crate::completions;
+use crate::traits::PushSubCommand;
+
+/// The function generated for a command or a sub-command, it converts the application into inputs and calls the function.
+pub type Dispatcher = fn(&Application) -> Result<i32, ParseError>;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CmdInfo {
+    pub name: &'static str,
+    // whether `#[completions]` is used
+    pub completions: bool,
+    // long names of options of the command
+    pub options: &'static [&'static str],
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SubInfo {
+    // name of the function, parents are referred by it
+    pub fn_name: &'static str,
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    // fn name of the parent sub-command if it's nested
+    pub parent: Option<&'static str>,
+    // names used by `conflicts_with`, `requires` and `required_unless` which are neither options nor arguments
+    // of the sub-command, they should be options of the command
+    pub globals: &'static [&'static str],
+}
+
+pub struct Registration {
+    pub info: SubInfo,
+    pub sub_command: fn() -> SubCommand,
+    pub call: Dispatcher,
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    true
+}
+
+const fn opt_str_eq(a: Option<&str>, b: Option<&str>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => str_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+const fn contains(names: &[&str], name: &str) -> bool {
+    let mut i = 0;
+
+    while i < names.len() {
+        if str_eq(names[i], name) {
+            return true;
+        }
+
+        i += 1;
+    }
+
+    false
+}
+
+const fn position(subs: &[SubInfo], fn_name: &str) -> Option<usize> {
+    let mut i = 0;
+
+    while i < subs.len() {
+        if str_eq(subs[i].fn_name, fn_name) {
+            return Some(i);
+        }
+
+        i += 1;
+    }
+
+    None
+}
+
+// the name or one of aliases of `sub` is `name`
+const fn is_named(sub: &SubInfo, name: &str) -> bool {
+    if str_eq(sub.name, name) {
+        return true;
+    }
+
+    let mut i = 0;
+
+    while i < sub.aliases.len() {
+        if str_eq(sub.aliases[i], name) {
+            return true;
+        }
+
+        i += 1;
+    }
+
+    false
+}
+
+// names (and aliases) of `a` and `b` overlap
+const fn is_conflicted(a: &SubInfo, b: &SubInfo) -> bool {
+    if is_named(b, a.name) {
+        return true;
+    }
+
+    let mut i = 0;
+
+    while i < a.aliases.len() {
+        if is_named(b, a.aliases[i]) {
+            return true;
+        }
+
+        i += 1;
+    }
+
+    false
+}
+
+/// Check sub-commands registered in `execute!`, it panics (i.e., fails to compile in a const context) if
+/// - the parent of a sub-command isn't registered,
+/// - sub-commands are nested circularly,
+/// - sub-commands which have the same parent have the same name (or alias),
+/// - `completions` is used by a sub-command of command which uses `#[completions]`,
+/// - constraints of options of a sub-command refer to names which are not defined.
+pub const fn check(cmd: &CmdInfo, subs: &[SubInfo]) {
+    let mut i = 0;
+
+    while i < subs.len() {
+        let sub = &subs[i];
+        let mut k = 0;
+
+        while k < sub.globals.len() {
+            if !contains(cmd.options, sub.globals[k]) {
+                panic!("unknown name in `conflicts_with`, `requires` or `required_unless` of an option of sub-command, use long names of options (without `--`) or names of arguments.");
+            }
+
+            k += 1;
+        }
+
+        if cmd.completions && sub.parent.is_none() && is_named(sub, completions::SUB_COMMAND_NAME) {
+            panic!("`completions` is a reserved sub-command because `#[completions]` is used, rename your sub-command.");
+        }
+
+        // nesting deeper than the number of sub-commands means a cycle
+        let mut parent = sub.parent;
+        let mut depth = 0;
+
+        while let Some(parent_name) = parent {
+            match position(subs, parent_name) {
+                Some(idx) => parent = subs[idx].parent,
+                None => panic!("try to register a sub-command whose parent is not registered, register its parent in the same `execute!()`."),
+            }
+
+            depth += 1;
+
+            if depth > subs.len() {
+                panic!("sub-commands are nested circularly, a sub-command can't be the ancestor of itself.");
+            }
+        }
+
+        let mut j = 0;
+
+        while j < i {
+            if opt_str_eq(subs[j].parent, sub.parent) && is_conflicted(&subs[j], sub) {
+                panic!("sub-command duplicate, define sub-commands with same name (or alias) more than once.");
+            }
+
+            j += 1;
+        }
+
+        i += 1;
+    }
+}
+
+fn print_completions(app: &Application) -> Result<i32, ParseError> {
+    completions::print_from_app(app).map(|_| 0)
+}
+
+// push sub-commands whose parent is `parent` (`None` means they belong to command) into `target`,
+// their nested sub-commands are pushed recursively.
+fn push_subs<T: PushSubCommand>(
+    target: &mut T,
+    subs: &[Registration],
+    parent: Option<&str>,
+    belong: &str,
+    prefix: &str,
+    dispatchers: &mut Vec<(String, Dispatcher)>,
+) {
+    for registration in subs.iter().filter(|registration| registration.info.parent == parent) {
+        let info = &registration.info;
+        let path = if prefix.is_empty() { info.name.to_string() } else { format!("{} {}", prefix, info.name) };
+        let mut sub = (registration.sub_command)();
+
+        sub.belong = belong.to_string();
+        dispatchers.push((path.clone(), registration.call));
+        push_subs(&mut sub, subs, Some(info.fn_name), &format!("{} {}", belong, info.name), &path, dispatchers);
+        target.push_sub_command(sub);
+    }
+}
+
+/// Push sub-commands registered (which have been checked by `check`) into `cmd`,
+/// returns dispatchers of sub-commands, whose keys are paths of sub-commands, e.g., `remote add`.
+pub fn assemble(cmd: &mut Command, info: &CmdInfo, subs: &[Registration]) -> Vec<(String, Dispatcher)> {
+    let mut dispatchers = vec![];
+    let belong = cmd.name.clone();
+
+    push_subs(cmd, subs, None, &belong, "", &mut dispatchers);
+
+    if info.completions {
+        cmd.push_sub_command(completions::sub_command(belong));
+        dispatchers.push((completions::SUB_COMMAND_NAME.to_string(), print_completions as Dispatcher));
+    }
+
+    dispatchers
+}
@@ -0,0 +1,154 @@
+//! Response files, i.e., arguments read from files, e.g., `@args.txt`.
+//!
+//! Arguments in the file are separated by whitespaces (including newlines) and quoted like shells:
+//! - `'...'` keeps everything inside as it is.
+//! - `"..."` keeps everything inside except `\"` and `\\`, which are `"` and `\`.
+//! - `\` outside quotes keeps the next character, e.g., `a\ b` is `a b`.
+//! - `#` at the beginning of an argument starts a comment which ends at the end of line.
+//!
+//! Response files can include other response files (relative paths are relative to the working directory),
+//! up to `MAX_DEPTH` levels. Arguments after `--` are never expanded.
+
+use crate::errors::ParseError;
+use std::ffi::OsString;
+
+/// The maximum levels of nested response files, it also stops response files which include themselves.
+pub const MAX_DEPTH: usize = 10;
+
+// `token` is the `@file` in argv which leads to the error, `path` is the file which can't be read or parsed
+fn invalid<T: Into<String>>(token: &str, path: &str, line: Option<usize>, message: T) -> ParseError {
+    ParseError::InvalidResponseFile {
+        token: token.to_string(),
+        path: path.to_string(),
+        line,
+        message: message.into(),
+        index: None,
+    }
+}
+
+/// Split the content of response file into arguments, the error is the line and the message.
+pub fn split(content: &str) -> Result<Vec<String>, (usize, String)> {
+    let mut args = vec![];
+    let mut chars = content.chars().peekable();
+    let mut line = 1;
+
+    loop {
+        // skip whitespaces between arguments
+        while let Some(c) = chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+
+            if *c == '\n' {
+                line += 1;
+            }
+
+            chars.next();
+        }
+
+        match chars.peek() {
+            None => break,
+            Some('#') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+
+                continue;
+            }
+            _ => {}
+        }
+
+        let mut arg = String::new();
+
+        while let Some(c) = chars.peek().copied() {
+            if c.is_whitespace() {
+                break;
+            }
+
+            chars.next();
+
+            match c {
+                '\'' | '"' => {
+                    let (quote, start) = (c, line);
+
+                    loop {
+                        match chars.next() {
+                            None => return Err((start, format!("unterminated quote `{}`", quote))),
+                            Some(c) if c == quote => break,
+                            Some('\\') if quote == '"' && matches!(chars.peek(), Some('"') | Some('\\')) => arg.extend(chars.next()),
+                            Some(c) => {
+                                if c == '\n' {
+                                    line += 1;
+                                }
+
+                                arg.push(c);
+                            }
+                        }
+                    }
+                }
+                '\\' => match chars.next() {
+                    Some('\n') => line += 1,
+                    Some(c) => arg.push(c),
+                    None => return Err((line, String::from("nothing follows `\\`"))),
+                },
+                _ => arg.push(c),
+            }
+        }
+
+        args.push(arg);
+    }
+
+    Ok(args)
+}
+
+/// Expand `@file` in `args` into arguments read from the file, the first element (the path of cli) is never expanded.
+/// `@` alone is an argument rather than a response file. Errors are located at the `@file` in `args`.
+pub fn expand(args: Vec<OsString>) -> Result<Vec<OsString>, ParseError> {
+    let mut expanded = vec![];
+    let mut opts_end = false;
+
+    for (idx, arg) in args.into_iter().enumerate() {
+        match arg.to_str().and_then(|arg| arg.strip_prefix('@')) {
+            Some(path) if idx > 0 && !opts_end && !path.is_empty() => {
+                expand_file(&arg.to_string_lossy(), path, 1, &mut expanded, &mut opts_end).map_err(|err| err.with_index(Some(idx)))?;
+            }
+            _ => {
+                opts_end = opts_end || arg == "--";
+                expanded.push(arg);
+            }
+        }
+    }
+
+    Ok(expanded)
+}
+
+// `token` is the `@file` in argv, `path` is the file to read, it may be included by other response files
+fn expand_file(token: &str, path: &str, depth: usize, expanded: &mut Vec<OsString>, opts_end: &mut bool) -> Result<(), ParseError> {
+    if depth > MAX_DEPTH {
+        return Err(invalid(token, path, None, format!("response files are nested more than {} levels", MAX_DEPTH)));
+    }
+
+    let bytes = std::fs::read(path).map_err(|err| invalid(token, path, None, format!("can't read it, {}", err)))?;
+    let content = String::from_utf8(bytes).map_err(|err| {
+        let valid = &err.as_bytes()[..err.utf8_error().valid_up_to()];
+        let line = valid.iter().filter(|b| **b == b'\n').count() + 1;
+
+        invalid(token, path, Some(line), "it isn't valid UTF-8")
+    })?;
+    let args = split(&content).map_err(|(line, message)| invalid(token, path, Some(line), message))?;
+
+    for arg in args {
+        match arg.strip_prefix('@') {
+            Some(inner) if !*opts_end && !inner.is_empty() => expand_file(token, inner, depth + 1, expanded, opts_end)?,
+            _ => {
+                *opts_end = *opts_end || arg == "--";
+                expanded.push(OsString::from(arg));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,609 @@
+use crate::{ Command, SubCommand, Options, Argument, ArgumentType, RepeatPolicy, OptionGroup, GroupKind, NegativeNumbers };
+use crate::config::Config;
+use crate::errors::SpecError;
+use crate::traits::{ GetArgs, GetOpts, GetSubCmds, PushArgument };
+use std::collections::HashSet;
+use std::str::FromStr;
+
+// the same as compile errors of macros
+const ARGUMENTS_ORDER_ERROR: &str = "all [optional arguments] should be placed after all <required arguments>";
+const MULTIPLY_ARGUMENT_IS_ONLY_LAST: &str = "only last argument could be multiply argument";
+const ARGUMENT_IS_NON_DUPLICATED: &str = "arguments duplicate";
+const REQUIRED_ARGUMENT_WITH_DEFAULT: &str = "required argument of command or sub-command can't have default value";
+const NEGATABLE_OPTION_PREFIX: &str = "negatable option should be defined as `--[no-]long-name`";
+const NEGATABLE_OPTION_IS_FLAG: &str = "negatable option can't accept arguments";
+const UNKNOWN_OPTION_PARAMETER: &str = "unknown parameter of option";
+const UNKNOWN_SUB_CMD_PARAMETER: &str = "unknown parameter of sub-command";
+const UNKNOWN_CMD_PARAMETER: &str = "unknown parameter of command, only `negative_numbers = value`, `config_option = \"long-name\"`, `config_file = \"path\"` and `response_files` are accepted";
+const UNKNOWN_CONSTRAINT_NAME: &str = "unknown name, names should be long names of options (without `--`) or names of arguments";
+const UNKNOWN_REPEAT_POLICY: &str = "unknown repeat policy, only `last`, `first`, `append` and `error` are accepted";
+const UNKNOWN_NEGATIVE_NUMBERS: &str = "unknown value of `negative_numbers`, only `auto`, `values` and `options` are accepted";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    // names, e.g., `install`, `dry` and `run` of `dry-run`
+    Ident(String),
+    // "description"
+    Str(String),
+    // one of `,=|<>[]-`
+    Punct(char),
+    // `..` or `...`
+    Dots,
+}
+
+// tokens with their byte offsets in spec
+fn tokenize(spec: &str) -> Result<Vec<(Token, usize)>, SpecError> {
+    let mut tokens = vec![];
+    let mut chars = spec.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        } else if c == '"' {
+            let mut value = String::new();
+            let mut terminated = false;
+
+            while let Some((_, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        terminated = true;
+                        break;
+                    }
+                    '\\' => match chars.next() {
+                        Some((_, 'n')) => value.push('\n'),
+                        Some((_, 't')) => value.push('\t'),
+                        Some((_, c)) => value.push(c),
+                        None => break,
+                    },
+                    c => value.push(c),
+                }
+            }
+
+            if !terminated {
+                return Err(SpecError::new(spec, start, "unterminated string"));
+            }
+
+            tokens.push((Token::Str(value), start));
+        } else if c == '.' {
+            let mut count = 1;
+
+            while chars.next_if(|(_, c)| *c == '.').is_some() {
+                count += 1;
+            }
+
+            if count != 2 && count != 3 {
+                return Err(SpecError::new(spec, start, "expected `..` or `...`"));
+            }
+
+            tokens.push((Token::Dots, start));
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut ident = c.to_string();
+
+            while let Some((_, c)) = chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_') {
+                ident.push(c);
+            }
+
+            tokens.push((Token::Ident(ident), start));
+        } else if ",=|<>[]-".contains(c) {
+            tokens.push((Token::Punct(c), start));
+        } else {
+            return Err(SpecError::new(spec, start, format!("unexpected character `{}`", c)));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct SpecParser<'a> {
+    spec: &'a str,
+    tokens: Vec<(Token, usize)>,
+    idx: usize,
+}
+
+impl<'a> SpecParser<'a> {
+    fn new(spec: &'a str) -> Result<Self, SpecError> {
+        Ok(SpecParser { spec, tokens: tokenize(spec)?, idx: 0 })
+    }
+
+    // position of the next token, it's the length of spec if all tokens are consumed
+    fn position(&self) -> usize {
+        self.tokens.get(self.idx).map_or(self.spec.len(), |(_, position)| *position)
+    }
+
+    fn error<T: Into<String>>(&self, message: T) -> SpecError {
+        SpecError::new(self.spec, self.position(), message)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.idx).map(|(token, _)| token)
+    }
+
+    fn peek_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.idx).map(|(token, _)| token.clone());
+
+        self.idx += 1;
+        token
+    }
+
+    fn punct(&mut self, c: char) -> Result<(), SpecError> {
+        if self.peek_punct(c) {
+            self.idx += 1;
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", c)))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, SpecError> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+
+                self.idx += 1;
+                Ok(ident)
+            }
+            _ => Err(self.error("expected a name")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, SpecError> {
+        match self.peek() {
+            Some(Token::Str(value)) => {
+                let value = value.clone();
+
+                self.idx += 1;
+                Ok(value)
+            }
+            _ => Err(self.error("expected a string, e.g., \"value\"")),
+        }
+    }
+
+    fn finish(&self) -> Result<(), SpecError> {
+        if self.idx < self.tokens.len() {
+            Err(self.error("unexpected input"))
+        } else {
+            Ok(())
+        }
+    }
+
+    // "dog-and-cat"
+    fn words(&mut self) -> Result<String, SpecError> {
+        let mut words = vec![self.ident()?];
+
+        while self.peek_punct('-') {
+            self.idx += 1;
+            words.push(self.ident()?);
+        }
+
+        Ok(words.join("-"))
+    }
+
+    // default value follows `=`, e.g., `= "8080"`
+    fn default(&mut self) -> Result<Option<String>, SpecError> {
+        if self.peek_punct('=') {
+            self.idx += 1;
+            Ok(Some(self.string()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // <a>, <..a>, [a], [..a], and `= "default"` could follow name or `>`, e.g., <a> = "0", [a = "0"]
+    fn argument(&mut self) -> Result<Argument, SpecError> {
+        let (required, close) = if self.peek_punct('<') {
+            (true, '>')
+        } else if self.peek_punct('[') {
+            (false, ']')
+        } else {
+            return Err(self.error("expected an argument, e.g., `<a>` or `[a]`"));
+        };
+
+        self.idx += 1;
+
+        let multiply = self.peek() == Some(&Token::Dots);
+
+        if multiply {
+            self.idx += 1;
+        }
+
+        let name = self.ident()?;
+        let mut default = if required { None } else { self.default()? };
+
+        self.punct(close)?;
+
+        if default.is_none() {
+            default = self.default()?;
+        }
+
+        let ty = match (required, multiply) {
+            (true, false) => ArgumentType::RequiredSingle,
+            (false, false) => ArgumentType::OptionalSingle,
+            (true, true) => ArgumentType::RequiredMultiple,
+            (false, true) => ArgumentType::OptionalMultiple,
+        };
+
+        Ok(Argument { name, ty, default, possible_values: None })
+    }
+
+    // `is_cmd` means arguments of command or sub-command, required arguments of them can't have default values
+    fn arguments(&mut self, is_cmd: bool) -> Result<Vec<Argument>, SpecError> {
+        let mut args: Vec<Argument> = vec![];
+        let mut names = HashSet::new();
+
+        while self.peek_punct('<') || self.peek_punct('[') {
+            let position = self.position();
+            let arg = self.argument()?;
+            let error = if args.last().is_some_and(|last| last.ty.is_multiply()) {
+                Some(MULTIPLY_ARGUMENT_IS_ONLY_LAST)
+            } else if arg.ty.is_required() && args.iter().any(|prev| !prev.ty.is_required()) {
+                Some(ARGUMENTS_ORDER_ERROR)
+            } else if names.contains(&arg.name) {
+                Some(ARGUMENT_IS_NON_DUPLICATED)
+            } else if is_cmd && arg.ty.is_required() && arg.default.is_some() {
+                Some(REQUIRED_ARGUMENT_WITH_DEFAULT)
+            } else {
+                None
+            };
+
+            if let Some(error) = error {
+                return Err(SpecError::new(self.spec, position, error));
+            }
+
+            names.insert(arg.name.clone());
+            args.push(arg);
+        }
+
+        Ok(args)
+    }
+
+    // `["a", "b"]` or `"a"`
+    fn names(&mut self) -> Result<Vec<String>, SpecError> {
+        if !self.peek_punct('[') {
+            return Ok(vec![self.string()?]);
+        }
+
+        let mut names = vec![];
+
+        self.idx += 1;
+
+        while !self.peek_punct(']') {
+            names.push(self.string()?);
+
+            if !self.peek_punct(']') {
+                self.punct(',')?;
+            }
+        }
+
+        self.idx += 1;
+        Ok(names)
+    }
+
+    // `, "description"` and `, key = value` until the end, `param` parses the value of key
+    fn params<F>(&mut self, desc: &mut Option<String>, mut param: F) -> Result<(), SpecError>
+        where F: FnMut(&mut Self, &str, usize) -> Result<(), SpecError> {
+        while self.peek_punct(',') {
+            self.idx += 1;
+
+            if desc.is_none() && matches!(self.peek(), Some(Token::Str(_))) {
+                *desc = Some(self.string()?);
+            } else {
+                let position = self.position();
+                let key = self.ident()?;
+
+                param(self, &key, position)?;
+            }
+        }
+
+        self.finish()
+    }
+
+    // [-s,] --[[no-]]long [<a> [b]][, "description"][, key = value]
+    fn option(&mut self) -> Result<Options, SpecError> {
+        let mut short = None;
+
+        self.punct('-')?;
+
+        if !self.peek_punct('-') {
+            let position = self.position();
+            let name = self.ident()?;
+
+            if name.chars().count() != 1 {
+                return Err(SpecError::new(self.spec, position, "short name of option should be one character"));
+            }
+
+            short = Some(name);
+            self.punct(',')?;
+            self.punct('-')?;
+        }
+
+        self.punct('-')?;
+
+        let negatable = self.peek_punct('[');
+
+        if negatable {
+            self.idx += 1;
+
+            if self.peek() != Some(&Token::Ident(String::from("no"))) {
+                return Err(self.error(NEGATABLE_OPTION_PREFIX));
+            }
+
+            self.idx += 1;
+            self.punct('-')?;
+            self.punct(']')?;
+        }
+
+        let long = self.words()?;
+        let position = self.position();
+        let opt_args = self.arguments(false)?;
+
+        if negatable && !opt_args.is_empty() {
+            return Err(SpecError::new(self.spec, position, NEGATABLE_OPTION_IS_FLAG));
+        }
+
+        let mut desc = None;
+        let mut options = Options::new(short, long, None);
+
+        options.negatable = negatable;
+
+        self.params(&mut desc, |parser, key, position| {
+            match key {
+                "required" => options.required = true,
+                "env" => {
+                    parser.punct('=')?;
+                    options.env = Some(parser.string()?);
+                }
+                "repeat" => {
+                    parser.punct('=')?;
+                    options.repeat = match parser.ident()?.as_str() {
+                        "last" => RepeatPolicy::LastWins,
+                        "first" => RepeatPolicy::FirstWins,
+                        "append" => RepeatPolicy::Append,
+                        "error" => RepeatPolicy::Error,
+                        _ => return Err(SpecError::new(parser.spec, position, UNKNOWN_REPEAT_POLICY)),
+                    };
+                }
+                "conflicts_with" | "requires" | "required_unless" => {
+                    parser.punct('=')?;
+
+                    let names = parser.names()?;
+
+                    match key {
+                        "conflicts_with" => options.conflicts_with = names,
+                        "requires" => options.requires = names,
+                        _ => options.required_unless = names,
+                    }
+                }
+                "exactly_one_of" | "at_least_one_of" => {
+                    parser.punct('=')?;
+
+                    let kind = if key == "exactly_one_of" { GroupKind::ExactlyOne } else { GroupKind::AtLeastOne };
+
+                    options.group = Some(OptionGroup { name: parser.string()?, kind });
+                }
+                _ => return Err(SpecError::new(parser.spec, position, UNKNOWN_OPTION_PARAMETER)),
+            }
+
+            Ok(())
+        })?;
+
+        options.desc = desc;
+
+        for arg in opt_args {
+            options.push_argument(arg);
+        }
+
+        Ok(options)
+    }
+
+    // name[|alias] [<a> <b> [c] [..d]][, "description"]
+    fn sub_command(&mut self) -> Result<SubCommand, SpecError> {
+        let name = self.ident()?;
+        let mut aliases = vec![];
+
+        while self.peek_punct('|') {
+            self.idx += 1;
+            aliases.push(self.ident()?);
+        }
+
+        let cmd_args = self.arguments(true)?;
+        // description could follow arguments without `,`
+        let mut desc = if matches!(self.peek(), Some(Token::Str(_))) { Some(self.string()?) } else { None };
+
+        // `parent = parent_fn_name` of macros refers to functions, it's meaningless here
+        self.params(&mut desc, |parser, _, position| Err(SpecError::new(parser.spec, position, UNKNOWN_SUB_CMD_PARAMETER)))?;
+
+        let mut sub_cmd = SubCommand::new(String::new(), name, desc);
+
+        sub_cmd.aliases = aliases;
+
+        for arg in cmd_args {
+            sub_cmd.push_argument(arg);
+        }
+
+        Ok(sub_cmd)
+    }
+
+    // ["version", ]name [<a> <b> [c] [..d]][, "description"][, negative_numbers = auto|values|options]
+    // [, config_option = "long-name"][, config_file = "path"][, response_files]
+    fn command(&mut self) -> Result<Command, SpecError> {
+        let version = if matches!(self.peek(), Some(Token::Str(_))) {
+            let version = self.string()?;
+
+            self.punct(',')?;
+            Some(version)
+        } else {
+            None
+        };
+        let name = self.ident()?;
+        let cmd_args = self.arguments(true)?;
+        let mut desc = None;
+        let mut negative_numbers = NegativeNumbers::Auto;
+        let mut config_option = None;
+        let mut config_file = None;
+        let mut response_files = false;
+
+        self.params(&mut desc, |parser, key, position| {
+            match key {
+                "negative_numbers" => {
+                    parser.punct('=')?;
+
+                    let position = parser.position();
+
+                    negative_numbers = match parser.bump() {
+                        Some(Token::Ident(value)) if value == "auto" => NegativeNumbers::Auto,
+                        Some(Token::Ident(value)) if value == "values" => NegativeNumbers::Values,
+                        Some(Token::Ident(value)) if value == "options" => NegativeNumbers::Options,
+                        _ => return Err(SpecError::new(parser.spec, position, UNKNOWN_NEGATIVE_NUMBERS)),
+                    };
+                }
+                "config_option" => {
+                    parser.punct('=')?;
+                    config_option = Some(parser.string()?);
+                }
+                "config_file" => {
+                    parser.punct('=')?;
+                    config_file = Some(parser.string()?);
+                }
+                "response_files" => response_files = true,
+                _ => return Err(SpecError::new(parser.spec, position, UNKNOWN_CMD_PARAMETER)),
+            }
+
+            Ok(())
+        })?;
+
+        let mut cmd = Command::new(name, desc);
+
+        if let Some(version) = version {
+            cmd.version = version;
+        }
+
+        cmd.negative_numbers = negative_numbers;
+        cmd.response_files = response_files;
+
+        if config_option.is_some() || config_file.is_some() {
+            cmd.config = Some(Config { option: config_option, path: config_file });
+        }
+
+        for arg in cmd_args {
+            cmd.push_argument(arg);
+        }
+
+        Ok(cmd)
+    }
+}
+
+// names of constraints of `defs` should be names of `defs`, `args` or `globals` (options of command for sub-commands),
+// the spec of error is the constraint, e.g., `requires = ["zip"]`
+fn check_constraint_names(defs: &[Options], args: &[Argument], globals: &[Options]) -> Result<(), SpecError> {
+    let is_defined = |name: &str| defs.iter().chain(globals.iter()).any(|opt| opt.long == name) || args.iter().any(|arg| arg.name == name);
+
+    for opt in defs {
+        let constraints = [("conflicts_with", &opt.conflicts_with), ("requires", &opt.requires), ("required_unless", &opt.required_unless)];
+
+        for (key, names) in constraints.iter() {
+            if let Some(idx) = names.iter().position(|name| !is_defined(name)) {
+                let quoted: Vec<String> = names.iter().map(|name| format!(r#""{}""#, name)).collect();
+                let spec = format!("{} = [{}]", key, quoted.join(", "));
+                let position = key.len() + 4 + quoted[..idx].iter().map(|name| name.len() + 2).sum::<usize>();
+
+                return Err(SpecError::new(&spec, position, UNKNOWN_CONSTRAINT_NAME));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check_sub_constraint_names(sub_cmd: &SubCommand, globals: &[Options]) -> Result<(), SpecError> {
+    check_constraint_names(sub_cmd.get_opts(), sub_cmd.get_args(), globals)?;
+
+    for nested in sub_cmd.get_sub_cmds() {
+        check_sub_constraint_names(nested, globals)?;
+    }
+
+    Ok(())
+}
+
+/// Check names used by `conflicts_with`, `requires` and `required_unless` of options once the command is assembled,
+/// they should be long names of options or names of arguments of the same command (or sub-command),
+/// options of sub-commands could refer to options of the command as well.
+/// Parsing options can't check them because options and arguments may be defined later.
+pub fn check_names(cmd: &Command) -> Result<(), SpecError> {
+    check_constraint_names(cmd.get_opts(), cmd.get_args(), &[])?;
+
+    for sub_cmd in cmd.get_sub_cmds() {
+        check_sub_constraint_names(sub_cmd, cmd.get_opts())?;
+    }
+
+    Ok(())
+}
+
+/// Parse an argument, e.g., `<a>`, `[..b]` or `[port = "8080"]`.
+pub fn parse_argument(spec: &str) -> Result<Argument, SpecError> {
+    let mut parser = SpecParser::new(spec)?;
+    let arg = parser.argument()?;
+
+    parser.finish()?;
+    Ok(arg)
+}
+
+/// Parse arguments separated by spaces, e.g., `<src> [..files]`.
+pub fn parse_arguments(spec: &str) -> Result<Vec<Argument>, SpecError> {
+    let mut parser = SpecParser::new(spec)?;
+    let args = parser.arguments(false)?;
+
+    parser.finish()?;
+    Ok(args)
+}
+
+/// Parse an option like `#[option]`, e.g., `-r, --registry <url>, "registry to use", env = "REGISTRY"`.
+pub fn parse_option(spec: &str) -> Result<Options, SpecError> {
+    SpecParser::new(spec)?.option()
+}
+
+/// Parse a sub-command like `#[sub_command]`, e.g., `install|i <pkg>, "install a package"`.
+/// `belong` of the sub-command is empty, and `parent = parent_fn_name` isn't accepted.
+pub fn parse_sub_command(spec: &str) -> Result<SubCommand, SpecError> {
+    SpecParser::new(spec)?.sub_command()
+}
+
+/// Parse a command like `#[command]`, e.g., `"0.1.0", npms [script], "package manager"`.
+pub fn parse_command(spec: &str) -> Result<Command, SpecError> {
+    SpecParser::new(spec)?.command()
+}
+
+impl FromStr for Argument {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_argument(s)
+    }
+}
+
+impl FromStr for Options {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_option(s)
+    }
+}
+
+impl FromStr for SubCommand {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_sub_command(s)
+    }
+}
+
+impl FromStr for Command {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_command(s)
+    }
+}
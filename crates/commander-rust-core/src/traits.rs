@@ -1,4 +1,6 @@
 use crate::{ Argument, SubCommand, Options };
+use crate::errors::report_error;
+use std::fmt::Display;
 
 pub trait GetArgs {
     fn get_args(&self) -> &Vec<Argument>;
@@ -8,6 +10,10 @@ pub trait GetOpts {
     fn get_opts(&self) -> &Vec<Options>;
 }
 
+pub trait GetSubCmds {
+    fn get_sub_cmds(&self) -> &Vec<SubCommand>;
+}
+
 pub trait PushSubCommand {
     fn push_sub_command(&mut self, _: SubCommand);
 }
@@ -20,6 +26,11 @@ pub trait PushArgument {
     fn push_argument(&mut self, _: Argument);
 }
 
+pub trait SetPossibleValues {
+    /// Set values accepted by the argument named `arg`, it's called with `FromArg::possible_values()`.
+    fn set_possible_values(&mut self, arg: &str, values: Option<Vec<&str>>);
+}
+
 pub trait ValidateArgs {
     fn validate_args(&self) -> bool;
 }
@@ -27,4 +38,36 @@ pub trait ValidateArgs {
 pub trait GetOpt {
     fn get_long_opt(&self, opt: &str) -> Option<&Options>;
     fn get_short_opt(&self, opt: &str) -> Option<&Options>;
-}
\ No newline at end of file
+}
+
+/// Return types of functions annotated with `#[command]` or `#[sub_command]` should implement it.
+/// `execute!` returns (or exits with) the exit code, `0` means success.
+pub trait IntoExitCode {
+    fn into_exit_code(self) -> i32;
+}
+
+impl IntoExitCode for () {
+    fn into_exit_code(self) -> i32 {
+        0
+    }
+}
+
+impl IntoExitCode for i32 {
+    fn into_exit_code(self) -> i32 {
+        self
+    }
+}
+
+/// The error is printed in the style of runtime error, and the exit code is `1`.
+/// Implement `IntoExitCode` for your own type if other exit codes are needed.
+impl<T: IntoExitCode, E: Display> IntoExitCode for Result<T, E> {
+    fn into_exit_code(self) -> i32 {
+        match self {
+            Ok(value) => value.into_exit_code(),
+            Err(err) => {
+                report_error(&err);
+                1
+            }
+        }
+    }
+}
@@ -9,4 +9,16 @@ fn argument_fmt_test() {
     assert_eq!(format!("{}", Argument::from("<...num>")), String::from("<..num>"));
     assert_eq!(format!("{}", Argument::from("[..num]")), String::from("[..num]"));
     assert_eq!(format!("{}", Argument::from("[...num]")), String::from("[..num]"));
-}
\ No newline at end of file
+}
+
+#[test]
+fn possible_values_fmt_test() {
+    let mut food = Argument::from("<food>");
+    let mut foods = Argument::from("[..foods]");
+
+    food.possible_values = Some(vec![String::from("noodles"), String::from("beef"), String::from("fish")]);
+    foods.possible_values = Some(vec![String::from("noodles"), String::from("beef")]);
+
+    assert_eq!(format!("{}", food), String::from("<food: noodles|beef|fish>"));
+    assert_eq!(format!("{}", foods), String::from("[..foods: noodles|beef]"));
+}
@@ -18,4 +18,70 @@ fn command_fmt_test() {
         format!("{}", command),
     );
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn default_values_fmt_test() {
+    use commander_rust_core::{Options, ArgumentType};
+    use commander_rust_core::traits::PushOptions;
+
+    let mut command = Command::from(r#"serve, "serve files""#);
+    let mut port = Options::from(r#"-p, --port, "port to listen""#);
+
+    port.push_argument(Argument {
+        name: String::from("port"),
+        ty: ArgumentType::RequiredSingle,
+        default: Some(String::from("8080")),
+        possible_values: None,
+    });
+    command.push_option(port);
+    command.push_argument(Argument {
+        name: String::from("host"),
+        ty: ArgumentType::OptionalSingle,
+        default: Some(String::from("localhost")),
+        possible_values: None,
+    });
+
+    assert_eq!(
+        "\u{1b}[1;3mDESCRIPTION\u{1b}[0m:\n    serve files\n\n\u{1b}[1;3mUSAGE\u{1b}[0m:\n    serve [host] [--global-options]\n\n\u{1b}[1;3mARGUMENTS\u{1b}[0m:\n    [host]    [default: localhost]\n\n\u{1b}[1;3mOPTIONS\u{1b}[0m:\n    -p, --port <port>    port to listen [default: 8080]\n\n",
+        format!("{}", command),
+    );
+}
+
+#[test]
+fn env_fmt_test() {
+    use commander_rust_core::{Options, ArgumentType};
+    use commander_rust_core::traits::PushOptions;
+
+    let mut command = Command::from(r#"deploy"#);
+    let mut token = Options::from(r#"-t, --token, "api token""#);
+
+    token.push_argument(Argument {
+        name: String::from("token"),
+        ty: ArgumentType::RequiredSingle,
+        default: Some(String::from("none")),
+        possible_values: None,
+    });
+    token.env = Some(String::from("APP_TOKEN"));
+    command.push_option(token);
+
+    assert!(format!("{}", command).contains("-t, --token <token>    api token [default: none] [env: APP_TOKEN]\n"));
+}
+
+#[test]
+fn negatable_fmt_test() {
+    use commander_rust_core::Options;
+    use commander_rust_core::traits::{PushOptions, GetOpt};
+
+    let mut command = Command::from(r#"print"#);
+
+    command.push_option(Options::from(r#"--[no-]color, "colorize the output""#));
+    command.push_option(Options::from(r#"-p, --[no-]pager, "page the output""#));
+
+    let help = format!("{}", command);
+
+    assert!(help.contains("        --[no-]color    colorize the output\n"));
+    assert!(help.contains("    -p, --[no-]pager    page the output\n"));
+    assert_eq!(command.get_long_opt("no-color").map(|opt| opt.long.as_str()), Some("color"));
+    assert!(command.get_long_opt("no-no-color").is_none());
+}
@@ -0,0 +1,97 @@
+use commander_rust_core::completions::{generate, Shell};
+use commander_rust_core::traits::{PushOptions, PushSubCommand};
+use commander_rust_core::{Command, Options, SubCommand};
+use std::str::FromStr;
+
+fn git() -> Command {
+    let mut cmd = Command::from(r#"git, "the stupid content tracker""#);
+    let mut remote = SubCommand::from(r#"git -> remote, "manage tracked repositories""#);
+    let mut add = SubCommand::from(r#"remote -> add <name> <url>, "add a remote""#);
+
+    add.push_option(Options::from(r#"-t, --track <branch>, "track the branch""#));
+    add.aliases.push(String::from("a"));
+    remote.push_sub_command(add);
+    cmd.push_sub_command(remote);
+    cmd.push_sub_command(SubCommand::from(r#"git -> clone <repo>, "clone a repository""#));
+    cmd.push_option(Options::from(r#"-h, --help, "print help information""#));
+    cmd.push_option(Options::from(r#"--git-dir <path>, "set the path to the repository""#));
+
+    cmd
+}
+
+#[test]
+fn shell_from_str() {
+    assert_eq!(Shell::from_str("bash"), Ok(Shell::Bash));
+    assert_eq!(Shell::from_str("ZSH"), Ok(Shell::Zsh));
+    assert_eq!(Shell::from_str("fish"), Ok(Shell::Fish));
+    assert!(Shell::from_str("tcsh").is_err());
+    assert_eq!(Shell::Zsh.to_string(), "zsh");
+}
+
+#[test]
+fn bash_completions() {
+    let script = generate(&git(), Shell::Bash);
+
+    assert!(script.contains(r#""remote:add") sub_path="remote add" ;;"#));
+    // aliases lead to the same sub-command
+    assert!(script.contains(r#""remote:a") sub_path="remote add" ;;"#));
+    assert!(script.contains(r#"compgen -W "remote clone --help -h --git-dir" -- "${cur}""#));
+    assert!(script.contains(r#"compgen -W "--track -t --help -h --git-dir" -- "${cur}""#));
+    assert!(script.contains("--track|-t|--git-dir)"));
+    assert!(script.ends_with("complete -F _git -o bashdefault -o default git\n"));
+}
+
+#[test]
+fn zsh_completions() {
+    let script = generate(&git(), Shell::Zsh);
+
+    assert!(script.starts_with("#compdef git\n"));
+    assert!(script.contains(r#""remote:add") sub_path="remote add" ;;"#));
+    assert!(script.contains(r#""remote:a") sub_path="remote add" ;;"#));
+    assert!(script.contains("'remote:manage tracked repositories'"));
+    assert!(script.contains("'-t:track the branch'"));
+    assert!(script.contains("--git-dir)\n                    _files"));
+}
+
+#[test]
+fn fish_completions() {
+    let script = generate(&git(), Shell::Fish);
+
+    assert!(script.contains("complete -c git -f\n"));
+    assert!(script.contains("case \"remote:a\"\n                set sub_path \"remote add\"\n"));
+    assert!(script.contains(r#"complete -c git -n '__fish_git_using_path ""' -a remote -d 'manage tracked repositories'"#));
+    assert!(script.contains(r#"complete -c git -n '__fish_git_using_path "remote add"' -s t -l track -r -F -d 'track the branch'"#));
+    assert!(script.contains(r#"complete -c git -n '__fish_git_using_path "remote"' -s h -l help -d 'print help information'"#));
+}
+
+#[test]
+fn shells_of_sub_command() {
+    use commander_rust_core::completions::{print_from_app, sub_command};
+    use commander_rust_core::converters::Application;
+    use commander_rust_core::errors::ParseError;
+    use commander_rust_core::parser::SegmentWrapper;
+    use commander_rust_core::traits::GetArgs;
+
+    let mut cmd = git();
+
+    cmd.push_sub_command(sub_command(cmd.name.clone()));
+
+    let completions = cmd.find_sub_cmd("completions").unwrap();
+
+    assert_eq!(
+        completions.get_args()[0].possible_values,
+        Some(vec![String::from("bash"), String::from("zsh"), String::from("fish")]),
+    );
+
+    let parser_result = SegmentWrapper::parse_from(&cmd, vec!["git", "completions", "tcsh"]);
+    let app = Application::from_parser_result(&parser_result, &cmd).unwrap();
+
+    assert_eq!(
+        print_from_app(&app),
+        Err(ParseError::InvalidValue {
+            token: String::from("tcsh"),
+            possible_values: vec![String::from("bash"), String::from("zsh"), String::from("fish")],
+            index: None,
+        }),
+    );
+}
@@ -0,0 +1,182 @@
+#![cfg(feature = "config")]
+
+use commander_rust_core::builder::Program;
+use commander_rust_core::config::{ load, ConfigValue };
+use commander_rust_core::converters::Application;
+use commander_rust_core::errors::ParseError;
+use commander_rust_core::parser::{ SegmentWrapper, Source };
+use commander_rust_core::spec::{ parse_command, parse_option, parse_sub_command };
+use commander_rust_core::traits::{ PushOptions, PushSubCommand };
+use commander_rust_core::Command;
+use std::path::PathBuf;
+
+fn git() -> Program {
+    Program::new("git")
+        .option("-C, --config <path>", "config file")
+        .option("--[no-]color", "colorize the output")
+        .option("--jobs <n>", "number of jobs")
+        .config_file("config", "")
+        .command("remote|rm")
+        .command("remote add <name> <url>")
+        .option("-t, --track <..branches>", "branches to track")
+        .option("--size <width> <height>", "size of window")
+        .option("--dry-run", "")
+}
+
+fn write(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("commander-rust-core-{}-{}", std::process::id(), name));
+
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+fn parse(program: &Program, argv: Vec<&str>) -> Result<Application, ParseError> {
+    parse_cmd(program.as_command(), argv)
+}
+
+fn parse_cmd(cmd: &Command, argv: Vec<&str>) -> Result<Application, ParseError> {
+    let (parser_result, sources) = SegmentWrapper::parse_with_sources(cmd, argv);
+
+    match parser_result {
+        Ok(_) => Ok(Application::from_parser_result(&parser_result, cmd).unwrap().with_sources(sources)),
+        Err(commander_rust_core::parser::TerminatorKind::Error(err)) => Err(err),
+        Err(_) => unreachable!(),
+    }
+}
+
+#[test]
+fn load_config() {
+    let toml = write("load.toml", "jobs = 4\nratio = 0.5\n[remote]\ncolor = true\n");
+    let json = write("load.json", r#"{ "jobs": 4, "tags": ["a", null], "color": null }"#);
+
+    assert_eq!(load(&toml), Ok(ConfigValue::Table(vec![
+        (String::from("jobs"), ConfigValue::Str(String::from("4"))),
+        (String::from("ratio"), ConfigValue::Str(String::from("0.5"))),
+        (String::from("remote"), ConfigValue::Table(vec![(String::from("color"), ConfigValue::Bool(true))])),
+    ])));
+    // null is regarded as absent
+    assert_eq!(load(&json), Ok(ConfigValue::Table(vec![
+        (String::from("jobs"), ConfigValue::Str(String::from("4"))),
+        (String::from("tags"), ConfigValue::Array(vec![
+            ConfigValue::Str(String::from("a")),
+            ConfigValue::Str(String::from("null")),
+        ])),
+    ])));
+
+    std::fs::remove_file(toml).unwrap();
+    std::fs::remove_file(json).unwrap();
+}
+
+#[test]
+fn config_options() {
+    let program = git();
+    let path = write("git.toml", r#"
+        color = false
+        jobs = 8
+
+        [remote.add]
+        track = ["main", "dev"]
+        size = [800, 600]
+        dry_run = true
+    "#);
+    let path_s = path.to_str().unwrap();
+    let config = |key: &str| Some(Source::Config { path: path.clone(), key: key.to_string() });
+    // tables are named after names of sub-commands rather than aliases
+    let app = parse(&program, vec!["git", "rm", "add", "origin", "url", "-C", path_s, "--jobs", "2"]).unwrap();
+
+    assert_eq!(app.get_flag("color"), Some(false));
+    assert_eq!(app.get_opt_source("color"), config("color"));
+    assert_eq!(app.get_opt_args("jobs").unwrap().to_string(), "2");
+    assert_eq!(app.get_opt_source("jobs"), Some(Source::Cli));
+    assert_eq!(app.get_opt_args("track").unwrap().to_string(), "main dev");
+    assert_eq!(app.get_opt_source("track"), config("remote.add.track"));
+    assert_eq!(app.get_opt_args("size").unwrap().to_string(), "800 600");
+    assert_eq!(app.get_opt_source("dry-run"), config("remote.add.dry_run"));
+    assert_eq!(app.get_opt_source("config"), Some(Source::Cli));
+
+    // tables of sub-commands not offered are ignored
+    let app = parse(&program, vec!["git", "remote", "--config", path_s, "--color"]).unwrap();
+
+    assert_eq!(app.get_flag("color"), Some(true));
+    assert_eq!(app.get_opt_source("color"), Some(Source::Cli));
+    assert_eq!(app.get_opt_source("jobs"), config("jobs"));
+    assert_eq!(app.get_opt_source("track"), None);
+
+    // without the option, there is no config file
+    let app = parse(&program, vec!["git", "remote"]).unwrap();
+
+    assert_eq!(app.get_opt_source("jobs"), None);
+    assert_eq!(Source::Env(String::from("GIT_JOBS")).to_string(), "environment variable `GIT_JOBS`");
+    assert_eq!(config("remote.add.track").unwrap().to_string(), format!("`remote.add.track` of config file `{}`", path_s));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn invalid_config() {
+    let program = git();
+    let cases = vec![
+        ("unknown.toml", "[remote]\nforce = true\n", "remote.force", "unknown key `remote.force`"),
+        ("flag.toml", "color = \"yes\"\n", "color", "`color` expects a boolean"),
+        ("table.toml", "[jobs]\nn = 1\n", "jobs", "`jobs` expects a value or an array of values"),
+        ("nested.toml", "[remote.add]\ntrack = [[\"a\"]]\n", "remote.add.track", "`remote.add.track` expects a value or an array of values"),
+        ("size.toml", "[remote.add]\nsize = 800\n", "remote.add.size", "arguments of `remote.add.size` are defined as `<width> <height>`"),
+    ];
+
+    for (name, content, key, message) in cases {
+        let path = write(name, content);
+        let token = path.to_string_lossy().to_string();
+
+        // values of sub-commands not offered are validated too
+        assert_eq!(
+            parse(&program, vec!["git", "-C", &token]).err(),
+            Some(ParseError::InvalidConfig { token: token.clone(), key: key.to_string(), message: message.to_string(), index: Some(2) }),
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    match parse(&program, vec!["git", "-C", "commander-rust-core-missing.toml"]) {
+        Err(ParseError::InvalidConfig { token, key, index, .. }) => {
+            assert_eq!((token.as_str(), key.as_str(), index), ("commander-rust-core-missing.toml", "", Some(2)));
+        }
+        other => panic!("unexpected result {:?}", other.map(|_| ())),
+    }
+}
+
+// the same as `#[command(npms, config_option = "config", config_file = "..")]`
+#[test]
+fn config_of_spec() {
+    let mut cmd = parse_command(r#"npms, "package manager", config_option = "config", config_file = "commander-rust-core-missing.toml""#).unwrap();
+    let mut install = parse_sub_command("install <pkg>").unwrap();
+
+    install.belong = String::from("npms");
+    install.push_option(parse_option("-g, --global").unwrap());
+    cmd.push_option(parse_option("-c, --config <path>").unwrap());
+    cmd.push_option(parse_option("-r, --registry <url>").unwrap());
+    cmd.push_sub_command(install);
+
+    let path = write("npms.toml", "registry = \"config.com\"\n[install]\nglobal = true\n");
+    let path_s = path.to_str().unwrap();
+    let config = |key: &str| Some(Source::Config { path: path.clone(), key: key.to_string() });
+
+    // the default location doesn't exist
+    let app = parse_cmd(&cmd, vec!["npms", "install", "pkg"]).unwrap();
+
+    assert_eq!(app.get_opt_source("registry"), None);
+
+    let app = parse_cmd(&cmd, vec!["npms", "install", "pkg", "-c", path_s]).unwrap();
+
+    assert_eq!(app.get_opt_args("registry").unwrap().to_string(), "config.com");
+    assert_eq!(app.get_opt_source("registry"), config("registry"));
+    assert_eq!(app.get_opt_source("global"), config("install.global"));
+
+    // command line > config file
+    let app = parse_cmd(&cmd, vec!["npms", "install", "pkg", "-c", path_s, "-r", "cli.com"]).unwrap();
+
+    assert_eq!(app.get_opt_args("registry").unwrap().to_string(), "cli.com");
+    assert_eq!(app.get_opt_source("registry"), Some(Source::Cli));
+    assert!(parse_cmd(&cmd, vec!["npms", "-c", "commander-rust-core-missing.toml"]).is_err());
+
+    std::fs::remove_file(path).unwrap();
+}
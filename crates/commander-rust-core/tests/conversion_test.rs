@@ -1,5 +1,3 @@
-#![feature(int_error_matching)]
-
 use commander_rust_core::converters::{Arg, Args, Application};
 use commander_rust_core::traits::{PushOptions, PushSubCommand};
 use commander_rust_core::converters::{FromArgs, FromArg, Mixed};
@@ -40,10 +38,10 @@ fn conversion_test() {
         "9086".to_string()
     ]
         .into_iter()
-        .map(|n| Arg(n))
+        .map(Arg::from)
         .collect()
     );
-    let arg = Arg("123".to_string());
+    let arg = Arg::from("123");
 
     test_ints! {arg =>
         u8 = 123;
@@ -79,13 +77,13 @@ fn conversion_test() {
     }
     ;
 
-    let err_u8 = u8::from_arg(&Arg("256".to_string()));
+    let err_u8 = u8::from_arg(&Arg::from("256"));
     assert!(err_u8.is_err());
 
-    let str = String::from_arg(&Arg("hello world!".to_string()));
+    let str = String::from_arg(&Arg::from("hello world!"));
     assert_eq!(Ok("hello world!".to_string()), str);
 
-    let path_buf = PathBuf::from_arg(&Arg("+-*".to_string()));
+    let path_buf = PathBuf::from_arg(&Arg::from("+-*"));
     assert_eq!(Ok(PathBuf::from("+-*")), path_buf);
 }
 
@@ -113,7 +111,7 @@ fn customize_arg_conversion_test() {
         Ok(MyU8 {
             num: 127,
         }),
-        MyU8::from_arg(&Arg("127".to_string()))
+        MyU8::from_arg(&Arg::from("127"))
     );
 }
 
@@ -152,7 +150,7 @@ fn customize_args_conversion_test() {
             name: "Jack".to_string(),
             age: 46,
         }),
-        Person::from_args(&Args(vec![Arg("Jack".to_string()), Arg("46".to_string())])),
+        Person::from_args(&Args(vec![Arg::from("Jack"), Arg::from("46")])),
     );
 }
 
@@ -313,4 +311,101 @@ fn converter_test() {
     assert!(!converter.contains_opt("s"));
     assert!(converter.contains_opt("u"));
     assert!(converter.contains_opt("url"));
-}
\ No newline at end of file
+}
+
+#[test]
+fn default_values_test() {
+    use commander_rust_core::{Argument, ArgumentType};
+    use commander_rust_core::traits::PushArgument;
+
+    let mut command = Command::from(r#"serve"#);
+    let mut port = Options::from(r#"-p, --port, "port to listen""#);
+    let mut quiet = Options::from(r#"-q, --quiet [level], "suppress output""#);
+
+    port.push_argument(Argument {
+        name: String::from("port"),
+        ty: ArgumentType::RequiredSingle,
+        default: Some(String::from("8080")),
+        possible_values: None,
+    });
+    quiet.push_argument(Argument {
+        name: String::from("level"),
+        ty: ArgumentType::OptionalSingle,
+        default: None,
+        possible_values: None,
+    });
+    command.push_argument(Argument {
+        name: String::from("host"),
+        ty: ArgumentType::OptionalSingle,
+        default: Some(String::from("localhost")),
+        possible_values: None,
+    });
+    command.push_argument(Argument {
+        name: String::from("paths"),
+        ty: ArgumentType::OptionalMultiple,
+        default: None,
+        possible_values: None,
+    });
+    command.push_option(port);
+    command.push_option(quiet);
+
+    let mut segments = SegmentWrapper(Segment::from_vec(vec![OsString::from("--quiet")]));
+    let app = Application::from_parser_result(&segments.parse_test(&command), &command).unwrap();
+
+    // [host] isn't offered, use default value
+    match app.get_cmd_arg("host") {
+        Some(Mixed::Single(arg)) => assert_eq!(arg.to_str(), Some("localhost")),
+        _ => panic!("default value of `host` should be used"),
+    }
+    // [..paths] isn't offered and has no default value, it's absent
+    assert!(app.get_cmd_arg("paths").is_none());
+    // [level] isn't offered and has no default value, it's absent
+    assert!(!app.global_opts["quiet"].contains_key("level"));
+    // --port isn't offered, but its arguments have default values
+    match app.global_opts.get("p").and_then(|port| port.get("port")) {
+        Some(Mixed::Single(arg)) => assert_eq!(arg.to_str(), Some("8080")),
+        _ => panic!("default value of `--port` should be used"),
+    }
+
+    let mut segments = SegmentWrapper(Segment::from_vec(vec![
+        OsString::from("example.com"),
+        OsString::from("-p"),
+        OsString::from("80"),
+    ]));
+    let app = Application::from_parser_result(&segments.parse_test(&command), &command).unwrap();
+
+    match (app.get_cmd_arg("host"), app.global_opts["port"].get("port")) {
+        (Some(Mixed::Single(host)), Some(Mixed::Single(port))) => {
+            assert_eq!(host.to_str(), Some("example.com"));
+            assert_eq!(port.to_str(), Some("80"));
+        }
+        _ => panic!("inputs should be used instead of default values"),
+    }
+
+    // absent argument
+    assert_eq!(<Option<u8> as FromArg>::from_absent(), Ok(None));
+    assert_eq!(<Option<Vec<u8>> as FromArgs>::from_absent(), Ok(None));
+    assert_eq!(<String as FromArg>::from_absent(), Ok(String::new()));
+    assert!(<u8 as FromArg>::from_absent().is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_conversion_test() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStringExt;
+    use std::path::Path;
+
+    let arg = Arg(OsString::from_vec(vec![b'a', 0xff]));
+
+    // paths and `OsString` are lossless
+    assert_eq!(PathBuf::from_arg(&arg), Ok(PathBuf::from(arg.0.clone())));
+    assert_eq!(<&Path>::from_arg(&arg), Ok(Path::new(&arg.0)));
+    assert_eq!(OsString::from_arg(&arg), Ok(arg.0.clone()));
+    assert_eq!(<&OsStr>::from_arg(&arg), Ok(arg.0.as_os_str()));
+    assert_eq!(String::from_arg(&arg), Err(()));
+    assert_eq!(<&str>::from_arg(&arg), Err(()));
+    assert!(u8::from_arg(&arg).is_err());
+    assert_eq!(String::from_args(&Args(vec![Arg::from("b"), arg.clone()])), Err(()));
+    assert_eq!(arg.to_string(), "a\u{fffd}");
+}
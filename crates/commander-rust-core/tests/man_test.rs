@@ -0,0 +1,80 @@
+use commander_rust_core::man::{render, render_sub, render_all};
+use commander_rust_core::traits::{PushOptions, PushSubCommand};
+use commander_rust_core::{Command, Options, SubCommand};
+
+fn git() -> Command {
+    let mut cmd = Command::from(r#""2.0.0", git <..paths>, "the stupid content tracker""#);
+    let mut remote = SubCommand::from(r#"git -> remote, "manage tracked repositories""#);
+    let mut add = SubCommand::from(r#"remote -> add <name> [url], "add a remote""#);
+
+    add.push_option(Options::from(r#"-t, --track <branch>, "track the branch""#));
+    remote.push_sub_command(add);
+    cmd.push_sub_command(remote);
+    cmd.push_option(Options::from(r#"--git-dir <path>, ".git by default""#));
+
+    cmd
+}
+
+#[test]
+fn render_command() {
+    let page = render(&git());
+
+    assert_eq!(
+        page,
+        [
+            r#".TH GIT 1 "" "git 2.0.0" "User Commands""#,
+            r".SH NAME",
+            r"git \- the stupid content tracker",
+            r".SH SYNOPSIS",
+            r"\fBgit\fR <..paths> [\fIoptions\fR] [\fIsub_commands\fR]",
+            r".SH DESCRIPTION",
+            r"the stupid content tracker",
+            r".SH OPTIONS",
+            r".TP",
+            r"\fB\-\-git\-dir\fR <path>",
+            r"\&.git by default",
+            r".SH SUB-COMMANDS",
+            r".TP",
+            r"\fBremote\fR",
+            r"manage tracked repositories",
+            r".SH VERSION",
+            r"2.0.0",
+            r"",
+        ].join("\n"),
+    );
+}
+
+#[test]
+fn render_sub_command() {
+    let cmd = git();
+    let page = render_sub(&cmd, "remote add").expect("`remote add` is defined");
+
+    assert!(page.starts_with(".TH GIT\\-REMOTE\\-ADD 1 \"\" \"git 2.0.0\" \"User Commands\"\n"));
+    assert!(page.contains("git\\-remote\\-add \\- add a remote\n"));
+    assert!(page.contains(".SH SYNOPSIS\n\\fBgit\\fR \\fBremote add\\fR <name> [url] [\\fIoptions\\fR]\n"));
+    assert!(page.contains(".SH OPTIONS\n.TP\n\\fB\\-t\\fR, \\fB\\-\\-track\\fR <branch>\ntrack the branch\n"));
+    assert!(page.contains(".SH GLOBAL OPTIONS\n.TP\n\\fB\\-\\-git\\-dir\\fR <path>\n"));
+    assert!(page.ends_with(".SH SEE ALSO\n\\fBgit\\fR(1)\n"));
+    assert!(render_sub(&cmd, "add").is_none());
+
+    let names: Vec<String> = render_all(&cmd).into_iter().map(|(name, _)| name).collect();
+
+    assert_eq!(names, vec!["git.1", "git-remote.1", "git-remote-add.1"]);
+}
+
+#[test]
+fn escape_quotes() {
+    let mut cmd = Command::from(r#"say <words>, "say something""#);
+    let mut quote = Options::from(r#"-q, --quote, "quote words""#);
+
+    quote.desc = Some(String::from(r#"wrap words in "double quotes""#));
+    cmd.push_option(quote);
+    cmd.version = String::from(r#"1.0 "beta""#);
+
+    let page = render(&cmd);
+
+    // quoted arguments of `.TH` are not ended by quotes of the version
+    assert!(page.starts_with(".TH SAY 1 \"\" \"say 1.0 \\(dqbeta\\(dq\" \"User Commands\"\n"));
+
+    assert!(page.contains("\\fB\\-q\\fR, \\fB\\-\\-quote\\fR\nwrap words in \\(dqdouble quotes\\(dq\n"));
+}
@@ -35,7 +35,7 @@ fn one_input() {
     assert!(output.is_ok());
 
     if let Ok(((cmd, sub), local_opts, global_opts)) = output {
-        assert_eq!(cmd, Some(Segment::Command(None, vec![Segment::Raw("arg".to_string())])));
+        assert_eq!(cmd, Some(Segment::Command(None, vec![Segment::Raw("arg".into(), Some(1))], None)));
         assert!(sub.is_none());
         assert!(local_opts.is_empty());
         assert!(global_opts.is_empty());
@@ -53,7 +53,7 @@ fn one_input() {
     assert!(output.is_ok());
 
     if let Ok(((cmd, sub), local_opts, global_opts)) = output {
-        assert_eq!(cmd, Some(Segment::Command(None, vec![Segment::Raw("arg".to_string())])));
+        assert_eq!(cmd, Some(Segment::Command(None, vec![Segment::Raw("arg".into(), Some(1))], None)));
         assert!(sub.is_none());
         assert!(local_opts.is_empty());
         assert!(global_opts.is_empty());
@@ -104,10 +104,11 @@ fn multiply_inputs() {
             Some(Segment::Command(
                 None,
                 vec![
-                    Segment::Raw("a".to_string()),
-                    Segment::Raw("b".to_string()),
-                    Segment::Raw("c".to_string())
-                ])
+                    Segment::Raw("a".into(), Some(1)),
+                    Segment::Raw("b".into(), Some(2)),
+                    Segment::Raw("c".into(), Some(3))
+                ],
+                None)
             )
         );
         assert!(sub.is_none());
@@ -133,9 +134,10 @@ fn multiply_inputs() {
             Some(Segment::Command(
                 None,
                 vec![
-                    Segment::Raw("a".to_string()),
-                    Segment::Raw("b".to_string()),
-                ])
+                    Segment::Raw("a".into(), Some(1)),
+                    Segment::Raw("b".into(), Some(2)),
+                ],
+                None)
             )
         );
         assert!(sub.is_none());
@@ -144,3 +146,375 @@ fn multiply_inputs() {
     }
 }
 
+
+#[test]
+fn parse_from_argv() {
+    use commander_rust_core::parser::TerminatorKind;
+    use commander_rust_core::traits::PushOptions;
+    use commander_rust_core::Options;
+
+    let mut cmd = Command::from(r#"test <arg1> [arg2], "test something""#);
+
+    cmd.push_option(Options::from(r#"-h, --help, "print help information""#));
+
+    // the first element is the path of cli, it should be ignored
+    let output = SegmentWrapper::parse_from(&cmd, vec!["/path/of/test", "a", "b"]);
+    let ((cmd_segs, sub), local_opts, global_opts) = output.expect("parse arguments failed");
+
+    assert_eq!(
+        cmd_segs,
+        Some(Segment::Command(
+            None,
+            vec![
+                Segment::Raw("a".into(), Some(1)),
+                Segment::Raw("b".into(), Some(2)),
+            ],
+            None)
+        )
+    );
+    assert!(sub.is_none());
+    assert!(local_opts.is_empty());
+    assert!(global_opts.is_empty());
+
+    let output = SegmentWrapper::parse_from(&cmd, vec![OsString::from("/path/of/test"), OsString::from("--help")]);
+
+    assert!(matches!(output, Err(TerminatorKind::GlobalHelp)));
+}
+
+#[test]
+fn parse_errors() {
+    use commander_rust_core::errors::ParseError;
+    use commander_rust_core::parser::TerminatorKind;
+    use commander_rust_core::traits::{PushOptions, PushSubCommand};
+    use commander_rust_core::{Options, SubCommand};
+
+    let mut cmd = Command::from(r#"test, "test something""#);
+    let mut sub_cmd = SubCommand::from(r#"test -> add <a> <b>"#);
+
+    sub_cmd.push_option(Options::from(r#"-o, --overflow <strategy>"#));
+    cmd.push_sub_command(sub_cmd);
+
+    let output = SegmentWrapper::parse_from(&cmd, vec!["test", "add", "1", "2", "--unknown"]);
+    match output {
+        Err(TerminatorKind::Error(err)) => {
+            assert_eq!(err, ParseError::UnknownOption { token: "--unknown".to_string(), suggestion: None, index: Some(4) });
+            assert_eq!(err.token(), "--unknown");
+            assert_eq!(err.index(), Some(4));
+        }
+        _ => panic!("`--unknown` is an unknown option"),
+    }
+
+    let output = SegmentWrapper::parse_from(&cmd, vec!["test", "add", "1"]);
+    match output {
+        Err(TerminatorKind::Error(err)) => assert_eq!(err, ParseError::MissingArgument {
+            token: "add".to_string(),
+            expected: "<a> <b>".to_string(),
+            index: Some(1),
+        }),
+        _ => panic!("argument `<b>` is missing"),
+    }
+
+    let output = SegmentWrapper::parse_from(&cmd, vec!["test", "add", "1", "2", "3"]);
+    match output {
+        Err(TerminatorKind::Error(err)) => assert_eq!(err, ParseError::TooManyArguments {
+            token: "3".to_string(),
+            expected: "<a> <b>".to_string(),
+            index: Some(4),
+        }),
+        _ => panic!("argument `3` is redundant"),
+    }
+
+    // the redundant one is located even if the same token appears before it
+    let output = SegmentWrapper::parse_from(&cmd, vec!["test", "add", "3", "2", "3"]);
+    match output {
+        Err(TerminatorKind::Error(err)) => assert_eq!(err.index(), Some(4)),
+        _ => panic!("argument `3` is redundant"),
+    }
+
+    let output = SegmentWrapper::parse_from(&cmd, vec!["test", "add", "1", "2", "-xo", "a"]);
+    match output {
+        Err(TerminatorKind::Error(err)) => {
+            assert_eq!(err.token(), "-x");
+            assert_eq!(err.index(), Some(4));
+        }
+        _ => panic!("`-x` is an unknown option"),
+    }
+
+    // values failed to be converted are located by the whole argument, `-v` isn't a part of `-xvz` or `-verbose`
+    let failed = ParseError::ConversionFailed { token: "-v".to_string(), ty: "i32".to_string(), index: None };
+    assert_eq!(failed.clone().locate(&["test", "-xvz", "-verbose", "-v"]).index(), Some(3));
+    assert_eq!(failed.locate(&["test", "-xvz", "-verbose"]).index(), None);
+
+    let output = SegmentWrapper::parse_from(&cmd, vec!["test", "add", "1", "2", "-o"]);
+    match output {
+        Err(TerminatorKind::Error(err)) => assert_eq!(err, ParseError::BadOptionArgument {
+            token: "-o".to_string(),
+            expected: "<strategy>".to_string(),
+            inputs: vec![],
+            index: Some(4),
+        }),
+        _ => panic!("argument of `-o` is missing"),
+    }
+
+    let output = SegmentWrapper::parse_from(&cmd, vec!["test", "1"]);
+    match output {
+        Err(TerminatorKind::Error(err)) => assert_eq!(err, ParseError::TooManyArguments {
+            token: "1".to_string(),
+            expected: String::new(),
+            index: Some(1),
+        }),
+        _ => panic!("command doesn't accept any argument"),
+    }
+}
+
+#[test]
+fn nested_sub_commands() {
+    use commander_rust_core::parser::TerminatorKind;
+    use commander_rust_core::traits::{PushOptions, PushSubCommand};
+    use commander_rust_core::{Options, SubCommand};
+
+    let mut cmd = Command::from(r#"git, "the stupid content tracker""#);
+    let mut remote = SubCommand::from(r#"git -> remote, "manage tracked repositories""#);
+    let mut add = SubCommand::from(r#"remote -> add <name> <url>, "add a remote""#);
+
+    add.push_option(Options::from(r#"-f, --fetch, "fetch the remote branches""#));
+    add.push_option(Options::from(r#"-h, --help, "print help information""#));
+    remote.push_sub_command(add);
+    remote.push_sub_command(SubCommand::from(r#"remote -> remove <name>, "remove a remote""#));
+    cmd.push_sub_command(remote);
+
+    assert_eq!(cmd.find_sub_cmd("remote add").map(|sub| sub.name.as_str()), Some("add"));
+    assert!(cmd.find_sub_cmd("add").is_none());
+    assert!(cmd.find_sub_cmd("remote push").is_none());
+
+    let output = SegmentWrapper::parse_from(&cmd, vec!["git", "remote", "add", "origin", "url", "-f"]);
+    let ((cmd_segs, sub), local_opts, global_opts) = output.expect("parse arguments failed");
+
+    assert!(cmd_segs.is_none());
+    assert_eq!(
+        sub,
+        Some(Segment::Command(
+            Some("remote add".to_string()),
+            vec![
+                Segment::Raw("origin".into(), Some(3)),
+                Segment::Raw("url".into(), Some(4)),
+            ],
+            Some(2))
+        )
+    );
+    assert_eq!(local_opts, vec![Segment::Short("f".to_string(), vec![], Some(5))]);
+    assert!(global_opts.is_empty());
+
+    // only the name right after its parent is parsed as nested sub-command
+    let output = SegmentWrapper::parse_from(&cmd, vec!["git", "remote", "remove", "add"]);
+    let ((_, sub), _, _) = output.expect("parse arguments failed");
+
+    assert_eq!(sub, Some(Segment::Command(Some("remote remove".to_string()), vec![Segment::Raw("add".into(), Some(3))], Some(2))));
+
+    let output = SegmentWrapper::parse_from(&cmd, vec!["git", "remote", "add", "--help"]);
+
+    match output {
+        Err(TerminatorKind::Help(sub_path)) => assert_eq!(sub_path, "remote add"),
+        _ => panic!("help information of `remote add` should be printed"),
+    }
+
+    let output = SegmentWrapper::parse_from(&cmd, vec!["git", "remote", "add", "origin"]);
+
+    match output {
+        Err(TerminatorKind::Error(err)) => {
+            assert_eq!(err.token(), "remote add");
+            assert_eq!(err.index(), Some(2));
+        }
+        _ => panic!("argument `<url>` is missing"),
+    }
+}
+
+#[test]
+fn suggestions() {
+    use commander_rust_core::errors::{ ParseError, suggest };
+    use commander_rust_core::parser::TerminatorKind;
+    use commander_rust_core::traits::{PushOptions, PushSubCommand};
+    use commander_rust_core::{Options, SubCommand};
+
+    let mut cmd = Command::from(r#"npms, "node package manager""#);
+    let mut install = SubCommand::from(r#"npms -> install <pkg>"#);
+    let mut remote = SubCommand::from(r#"npms -> remote"#);
+
+    install.push_option(Options::from(r#"-f, --force"#));
+    remote.push_sub_command(SubCommand::from(r#"npms -> add <name>"#));
+    cmd.push_option(Options::from(r#"--verbose"#));
+    cmd.push_sub_command(install);
+    cmd.push_sub_command(remote);
+
+    let unknown_sub = |argv: Vec<&str>| match SegmentWrapper::parse_from(&cmd, argv) {
+        Err(TerminatorKind::Error(ParseError::UnknownSubCommand { token, suggestion, index })) => (token, suggestion, index),
+        other => panic!("unexpected result {:?}", other),
+    };
+
+    assert_eq!(unknown_sub(vec!["npms", "instal", "react"]), (String::from("instal"), Some(String::from("install")), Some(1)));
+    // options after misspelled sub-command are not reported
+    assert_eq!(unknown_sub(vec!["npms", "instal", "react", "-f"]), (String::from("instal"), Some(String::from("install")), Some(1)));
+    assert_eq!(unknown_sub(vec!["npms", "remote", "ad", "origin"]), (String::from("ad"), Some(String::from("add")), Some(2)));
+
+    let unknown_opt = |argv: Vec<&str>| match SegmentWrapper::parse_from(&cmd, argv) {
+        Err(TerminatorKind::Error(ParseError::UnknownOption { token, suggestion, .. })) => (token, suggestion),
+        other => panic!("unexpected result {:?}", other),
+    };
+
+    assert_eq!(unknown_opt(vec!["npms", "install", "react", "--forse"]), (String::from("--forse"), Some(String::from("--force"))));
+    // global options are suggested too
+    assert_eq!(unknown_opt(vec!["npms", "install", "react", "--verbos"]), (String::from("--verbos"), Some(String::from("--verbose"))));
+    assert_eq!(unknown_opt(vec!["npms", "--verbse"]), (String::from("--verbse"), Some(String::from("--verbose"))));
+    assert_eq!(unknown_opt(vec!["npms", "install", "react", "--global"]), (String::from("--global"), None));
+    assert_eq!(unknown_opt(vec!["npms", "install", "react", "-g"]), (String::from("-g"), None));
+
+    assert_eq!(suggest("instal", vec!["install", "uninstall", "update"]), Some(String::from("install")));
+    assert_eq!(suggest("xyz", vec!["install", "uninstall", "update"]), None);
+
+    let err = ParseError::UnknownOption { token: String::from("--forse"), suggestion: Some(String::from("--force")), index: None };
+
+    assert_eq!(err.to_string(), "Unknown option: `\u{1b}[1m--forse\u{1b}[0m`. Did you mean `\u{1b}[1m--force\u{1b}[0m`?");
+}
+
+#[test]
+fn repeated_options() {
+    use commander_rust_core::errors::ParseError;
+    use commander_rust_core::parser::TerminatorKind;
+    use commander_rust_core::traits::PushOptions;
+    use commander_rust_core::{Options, RepeatPolicy};
+
+    let mut cmd = Command::from(r#"cc [file], "compile files""#);
+    let mut output = Options::from(r#"-o, --output <file>"#);
+
+    output.repeat = RepeatPolicy::Error;
+    cmd.push_option(output);
+    cmd.push_option(Options::from(r#"-I, --include <dir>"#));
+
+    let repeated = |argv: Vec<&str>| match SegmentWrapper::parse_from(&cmd, argv) {
+        Err(TerminatorKind::Error(ParseError::RepeatedOption { token, index })) => Some((token, index)),
+        _ => None,
+    };
+
+    assert!(SegmentWrapper::parse_from(&cmd, vec!["cc", "main.c", "-o", "main", "-I", "a", "-I", "b"]).is_ok());
+    assert_eq!(
+        repeated(vec!["cc", "main.c", "-o", "main", "-I", "a", "--output", "app"]),
+        Some((String::from("--output"), Some(6))),
+    );
+    // the repeated one is located even if it's the same as the first one
+    assert_eq!(repeated(vec!["cc", "main.c", "-o", "main", "-o", "app"]), Some((String::from("-o"), Some(4))));
+}
+
+#[test]
+fn required_options() {
+    use commander_rust_core::errors::ParseError;
+    use commander_rust_core::parser::TerminatorKind;
+    use commander_rust_core::traits::{PushOptions, PushSubCommand};
+    use commander_rust_core::{Options, SubCommand};
+
+    let mut cmd = Command::from(r#"git, "version control""#);
+    let mut add = SubCommand::from(r#"git -> add <name> <url>"#);
+    let mut remote = SubCommand::from(r#"git -> remote"#);
+    let mut fetch = Options::from(r#"-f, --fetch"#);
+    let mut user = Options::from(r#"-u, --user <name>, "name of user""#);
+
+    fetch.required = true;
+    user.required = true;
+    add.push_option(fetch);
+    remote.push_sub_command(add);
+    cmd.push_sub_command(remote);
+    cmd.push_option(user);
+
+    assert!(format!("{}", cmd).contains("-u, --user <name>    name of user [required]\n"));
+
+    let missing = |argv: Vec<&str>| match SegmentWrapper::parse_from(&cmd, argv) {
+        Err(TerminatorKind::Error(err @ ParseError::MissingRequiredOption { .. })) => Some(err),
+        _ => None,
+    };
+
+    assert!(SegmentWrapper::parse_from(&cmd, vec!["git", "remote", "add", "origin", "url", "-f", "-u", "me"]).is_ok());
+    assert_eq!(
+        missing(vec!["git", "remote", "add", "origin", "url", "-u", "me"]),
+        Some(ParseError::MissingRequiredOption { token: String::from("--fetch"), cmd: String::from("remote add"), index: None }),
+    );
+    assert_eq!(
+        missing(vec!["git"]),
+        Some(ParseError::MissingRequiredOption { token: String::from("--user"), cmd: String::from("git"), index: None }),
+    );
+    assert_eq!(
+        missing(vec!["git", "remote"]).map(|err| err.to_string()),
+        Some(String::from("Missing required option: `\u{1b}[1m--user\u{1b}[0m` is required by `\u{1b}[1mgit\u{1b}[0m`.")),
+    );
+}
+
+#[test]
+fn option_constraints() {
+    use commander_rust_core::errors::ParseError;
+    use commander_rust_core::parser::TerminatorKind;
+    use commander_rust_core::traits::{PushOptions, PushSubCommand};
+    use commander_rust_core::{Options, SubCommand, OptionGroup, GroupKind};
+
+    let mut cmd = Command::from(r#"pack, "pack files""#);
+    let mut build = SubCommand::from(r#"pack -> build [dir]"#);
+    let mut json = Options::from(r#"--json"#);
+    let mut yaml = Options::from(r#"--yaml"#);
+    let mut zip = Options::from(r#"-z, --zip"#);
+    let mut level = Options::from(r#"-l, --level <n>"#);
+    let mut name = Options::from(r#"-n, --name <name>"#);
+    let format = OptionGroup { name: String::from("format"), kind: GroupKind::ExactlyOne };
+
+    json.group = Some(format.clone());
+    yaml.group = Some(format);
+    zip.conflicts_with = vec![String::from("dir")];
+    level.requires = vec![String::from("zip")];
+    name.required_unless = vec![String::from("dir")];
+    build.push_option(zip);
+    build.push_option(level);
+    build.push_option(name);
+    cmd.push_sub_command(build);
+    cmd.push_option(json);
+    cmd.push_option(yaml);
+
+    assert!(format!("{}", cmd).contains("--json    [group: format, exactly one]\n"));
+
+    let parse = |argv: Vec<&str>| match SegmentWrapper::parse_from(&cmd, argv) {
+        Err(TerminatorKind::Error(err)) => Some(err),
+        _ => None,
+    };
+
+    assert_eq!(parse(vec!["pack", "build", "src", "--json"]), None);
+    assert_eq!(parse(vec!["pack", "build", "-z", "-l", "9", "-n", "app", "--yaml"]), None);
+    assert_eq!(
+        parse(vec!["pack", "build", "src", "--json", "--yaml"]),
+        Some(ParseError::GroupViolation {
+            token: String::from("--yaml"),
+            group: String::from("format"),
+            members: vec![String::from("--json"), String::from("--yaml")],
+            index: Some(4),
+        }),
+    );
+    assert_eq!(
+        parse(vec!["pack", "build", "src"]),
+        Some(ParseError::GroupViolation {
+            token: String::new(),
+            group: String::from("format"),
+            members: vec![String::from("--json"), String::from("--yaml")],
+            index: None,
+        }),
+    );
+    assert_eq!(
+        parse(vec!["pack", "build", "src", "-z", "--json"]),
+        Some(ParseError::ConflictingOptions { token: String::from("-z"), conflict: String::from("dir"), index: Some(3) }),
+    );
+    assert_eq!(
+        parse(vec!["pack", "build", "src", "-l", "1", "--json"]),
+        Some(ParseError::MissingDependency { token: String::from("-l"), required: String::from("--zip"), index: Some(3) }),
+    );
+    assert_eq!(
+        parse(vec!["pack", "build", "--json"]),
+        Some(ParseError::MissingConditionalOption {
+            token: String::from("--name"),
+            unless: vec![String::from("dir")],
+            index: None,
+        }),
+    );
+}
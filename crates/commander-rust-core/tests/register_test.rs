@@ -0,0 +1,93 @@
+use commander_rust_core::converters::Application;
+use commander_rust_core::errors::ParseError;
+use commander_rust_core::register::{ assemble, check, CmdInfo, SubInfo, Registration };
+use commander_rust_core::{ Command, SubCommand };
+
+const GIT: CmdInfo = CmdInfo { name: "git", completions: true, options: &["verbose"] };
+
+const fn sub(fn_name: &'static str, name: &'static str, aliases: &'static [&'static str], parent: Option<&'static str>) -> SubInfo {
+    SubInfo { fn_name, name, aliases, parent, globals: &[] }
+}
+
+const REMOTE: SubInfo = sub("remote", "remote", &["rm"], None);
+const REMOTE_ADD: SubInfo = sub("remote_add", "add", &[], Some("remote"));
+// nested sub-commands with same name are allowed if their parents are different
+const ADD: SubInfo = sub("add", "add", &["a"], None);
+
+// errors are reported when compiling
+const _: () = check(&GIT, &[REMOTE_ADD, REMOTE, ADD]);
+
+fn remote() -> SubCommand {
+    SubCommand::new(String::new(), String::from("remote"), None)
+}
+
+fn remote_add() -> SubCommand {
+    SubCommand::new(String::new(), String::from("add"), None)
+}
+
+fn add() -> SubCommand {
+    SubCommand::new(String::new(), String::from("add"), None)
+}
+
+fn call(_: &Application) -> Result<i32, ParseError> {
+    Ok(0)
+}
+
+#[test]
+fn assemble_sub_commands() {
+    let mut cmd = Command::new(String::from("git"), None);
+    let subs = [
+        Registration { info: REMOTE_ADD, sub_command: remote_add, call },
+        Registration { info: REMOTE, sub_command: remote, call },
+        Registration { info: ADD, sub_command: add, call },
+    ];
+    let dispatchers = assemble(&mut cmd, &GIT, &subs);
+    let paths: Vec<&str> = dispatchers.iter().map(|(path, _)| path.as_str()).collect();
+
+    assert_eq!(paths, vec!["remote", "remote add", "add", "completions"]);
+    assert_eq!(cmd.find_sub_cmd("remote").map(|sub| sub.belong.as_str()), Some("git"));
+    assert_eq!(cmd.find_sub_cmd("remote add").map(|sub| sub.belong.as_str()), Some("git remote"));
+    assert_eq!(cmd.find_sub_cmd("add").map(|sub| sub.belong.as_str()), Some("git"));
+    assert!(cmd.get_sub_cmd("completions").is_some());
+}
+
+#[test]
+#[should_panic(expected = "whose parent is not registered")]
+fn unknown_parent() {
+    check(&GIT, &[REMOTE_ADD, ADD]);
+}
+
+#[test]
+#[should_panic(expected = "nested circularly")]
+fn circular_parents() {
+    check(&GIT, &[sub("a", "a", &[], Some("b")), sub("b", "b", &[], Some("a"))]);
+}
+
+#[test]
+#[should_panic(expected = "sub-command duplicate")]
+fn duplicated_names() {
+    check(&GIT, &[REMOTE, sub("rm", "rm", &[], None)]);
+}
+
+#[test]
+#[should_panic(expected = "`completions` is a reserved sub-command")]
+fn reserved_completions() {
+    check(&GIT, &[sub("completions", "completions", &[], None)]);
+}
+
+#[test]
+fn completions_not_used() {
+    check(&CmdInfo { name: "git", completions: false, options: &[] }, &[sub("completions", "completions", &[], None)]);
+}
+
+#[test]
+fn constraints_of_sub_commands() {
+    // options of sub-commands could refer to options of the command
+    check(&GIT, &[SubInfo { globals: &["verbose"], ..REMOTE }]);
+}
+
+#[test]
+#[should_panic(expected = "unknown name in `conflicts_with`")]
+fn unknown_constraint_names() {
+    check(&GIT, &[SubInfo { globals: &["verbos"], ..REMOTE }]);
+}
@@ -0,0 +1,139 @@
+use commander_rust_core::errors::ParseError;
+use commander_rust_core::converters::{ Application, Mixed };
+use commander_rust_core::parser::{ SegmentWrapper, TerminatorKind };
+use commander_rust_core::response::{ expand, split, MAX_DEPTH };
+use commander_rust_core::spec::parse_command;
+use std::ffi::OsString;
+
+fn write(name: &str, content: &[u8]) -> String {
+    let path = std::env::temp_dir().join(format!("commander-rust-response-{}-{}", std::process::id(), name));
+
+    std::fs::write(&path, content).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn os(args: Vec<&str>) -> Vec<OsString> {
+    args.into_iter().map(OsString::from).collect()
+}
+
+// the `@file` is always the first argument after the path of cli
+fn error(token: &str, path: &str, line: Option<usize>, message: &str) -> ParseError {
+    ParseError::InvalidResponseFile {
+        token: token.to_string(),
+        path: path.to_string(),
+        line,
+        message: message.to_string(),
+        index: Some(1),
+    }
+}
+
+#[test]
+fn split_content() {
+    assert_eq!(split("  -o out.txt\n\t--level 3  "), Ok(vec!["-o", "out.txt", "--level", "3"].into_iter().map(String::from).collect()));
+    assert_eq!(
+        split(r#"'a b' "c \"d\" \\ \n" e\ f g"h"'i' ''"#),
+        Ok(vec!["a b", r#"c "d" \ \n"#, "e f", "ghi", ""].into_iter().map(String::from).collect()),
+    );
+    // comments start at the beginning of arguments
+    assert_eq!(
+        split("# comment 'x\n-v a#b # another\n\"multi\nline\""),
+        Ok(vec!["-v", "a#b", "multi\nline"].into_iter().map(String::from).collect()),
+    );
+    assert_eq!(split("a\\\nb"), Ok(vec![String::from("ab")]));
+    assert_eq!(split("a\n\n 'b\nc"), Err((3, String::from("unterminated quote `'`"))));
+    assert_eq!(split("a\n\"b\nc"), Err((2, String::from("unterminated quote `\"`"))));
+    assert_eq!(split("a\nb\\"), Err((2, String::from("nothing follows `\\`"))));
+}
+
+#[test]
+fn expand_files() {
+    let inner = write("inner.txt", b"--level 3 -- @not-expanded");
+    let outer = write("outer.txt", format!("'out put.txt'\n@{}\n", inner).as_bytes());
+    let outer_token = format!("@{}", outer);
+
+    assert_eq!(
+        expand(os(vec!["@cli", "-o", &outer_token, "@"])),
+        Ok(os(vec!["@cli", "-o", "out put.txt", "--level", "3", "--", "@not-expanded", "@"])),
+    );
+    // arguments after `--` are never expanded
+    assert_eq!(expand(os(vec!["cli", "--", &outer_token])), Ok(os(vec!["cli", "--", &outer_token])));
+
+    let bad_quote = write("bad_quote.txt", b"-o\n'out");
+    let bad_utf8 = write("bad_utf8.txt", b"-o\n\n\xff");
+    let includes_bad = write("includes_bad.txt", format!("-v @{}", bad_quote).as_bytes());
+    let recursive = write("recursive.txt", b"");
+
+    std::fs::write(&recursive, format!("-v @{}", recursive)).unwrap();
+
+    let token = |path: &str| format!("@{}", path);
+
+    assert_eq!(
+        expand(os(vec!["cli", &token(&bad_quote)])),
+        Err(error(&token(&bad_quote), &bad_quote, Some(2), "unterminated quote `'`")),
+    );
+    assert_eq!(
+        expand(os(vec!["cli", &token(&bad_utf8)])),
+        Err(error(&token(&bad_utf8), &bad_utf8, Some(3), "it isn't valid UTF-8")),
+    );
+    // the file included is reported, the token is the one in argv
+    assert_eq!(
+        expand(os(vec!["cli", &token(&includes_bad)])),
+        Err(error(&token(&includes_bad), &bad_quote, Some(2), "unterminated quote `'`")),
+    );
+    assert_eq!(
+        expand(os(vec!["cli", &token(&recursive)])),
+        Err(error(&token(&recursive), &recursive, None, &format!("response files are nested more than {} levels", MAX_DEPTH))),
+    );
+
+    match expand(os(vec!["cli", "@commander-rust-missing.txt"])) {
+        Err(ParseError::InvalidResponseFile { token, path, line, message, .. }) => {
+            assert_eq!((token.as_str(), path.as_str(), line), ("@commander-rust-missing.txt", "commander-rust-missing.txt", None));
+            assert!(message.starts_with("can't read it"));
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    for path in [inner, outer, bad_quote, bad_utf8, includes_bad, recursive].iter() {
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+// the same as `#[command(cc <..files>, response_files)]`
+#[test]
+fn response_files_of_spec() {
+    let cmd = parse_command("cc <..files>, response_files").unwrap();
+    let path = write("spec.txt", b"a.c 'b c.c'");
+    let token = format!("@{}", path);
+    let result = SegmentWrapper::parse_from(&cmd, vec!["cc", &token, "d.c"]);
+    let app = Application::from_parser_result(&result, &cmd).unwrap();
+
+    match app.get_cmd_arg("files") {
+        Some(Mixed::Multiply(files)) => assert_eq!(files.to_string(), "a.c b c.c d.c"),
+        other => panic!("unexpected argument {:?}", other),
+    }
+
+    std::fs::remove_file(path).unwrap();
+}
+
+// conversion errors are located in the same argv as errors of parsing
+#[test]
+fn errors_located_in_expanded_argv() {
+    let cmd = parse_command("sum <..numbers>, response_files").unwrap();
+    let path = write("located.txt", b"1 2");
+    let token = format!("@{}", path);
+    let mut argv = os(vec!["sum", &token, "--unknown"]);
+
+    match SegmentWrapper::parse_argv(&cmd, &mut argv).0 {
+        Err(TerminatorKind::Error(err)) => assert_eq!(err.index(), Some(3)),
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    let mut argv = os(vec!["sum", &token, "x"]);
+    let err = ParseError::ConversionFailed { token: String::from("x"), ty: String::from("u32"), index: None };
+
+    assert!(SegmentWrapper::parse_argv(&cmd, &mut argv).0.is_ok());
+    assert_eq!(argv, os(vec!["sum", "1", "2", "x"]));
+    assert_eq!(err.locate(&argv).index(), Some(3));
+
+    std::fs::remove_file(path).unwrap();
+}
@@ -34,8 +34,8 @@ fn parse_test() {
     let segments = Segment::from_vec(args_os);
     assert_eq!(
         vec![
-            Segment::Raw(String::from("cli")),
-            Segment::Raw(String::from("sub_command"))
+            Segment::Raw("cli".into(), Some(1)),
+            Segment::Raw("sub_command".into(), Some(2))
         ],
         segments,
     );
@@ -44,9 +44,9 @@ fn parse_test() {
     let segments = Segment::from_vec(args_os);
     assert_eq!(
         vec![
-            Segment::Raw(String::from("cli")),
-            Segment::Short("a".to_string(), vec![]),
-            Segment::Raw(String::from("hello world!"))
+            Segment::Raw("cli".into(), Some(1)),
+            Segment::Short("a".to_string(), vec![], Some(2)),
+            Segment::Raw("hello world!".into(), Some(3))
         ],
         segments,
     );
@@ -55,9 +55,9 @@ fn parse_test() {
     let segments = Segment::from_vec(args_os);
     assert_eq!(
         vec![
-            Segment::Raw(String::from("cli")),
-            Segment::Long(String::from("long-options"), vec![]),
-            Segment::Raw(String::from("hello world!"))
+            Segment::Raw("cli".into(), Some(1)),
+            Segment::Long(String::from("long-options"), vec![], Some(2)),
+            Segment::Raw("hello world!".into(), Some(3))
         ],
         segments,
     );
@@ -66,9 +66,9 @@ fn parse_test() {
     let segments = Segment::from_vec(args_os);
     assert_eq!(
         vec![
-            Segment::Raw(String::from("cli")),
-            Segment::Long(String::from("long-options"), vec![Segment::Raw(String::from("/path/to/output"))]),
-            Segment::Raw(String::from("hello world!"))
+            Segment::Raw("cli".into(), Some(1)),
+            Segment::Long(String::from("long-options"), vec![Segment::Raw("/path/to/output".into(), Some(2))], Some(2)),
+            Segment::Raw("hello world!".into(), Some(3))
         ],
         segments,
     );
@@ -77,9 +77,9 @@ fn parse_test() {
     let segments = Segment::from_vec(args_os);
     assert_eq!(
         vec![
-            Segment::Raw(String::from("cli")),
-            Segment::Long(String::from("js-expr"), vec![Segment::Raw(String::from("let a = 123;"))]),
-            Segment::Raw(String::from("hello world!"))
+            Segment::Raw("cli".into(), Some(1)),
+            Segment::Long(String::from("js-expr"), vec![Segment::Raw("let a = 123;".into(), Some(2))], Some(2)),
+            Segment::Raw("hello world!".into(), Some(3))
         ],
         segments,
     );
@@ -88,11 +88,11 @@ fn parse_test() {
     let segments = Segment::from_vec(args_os);
     assert_eq!(
         vec![
-            Segment::Raw(String::from("cli")),
+            Segment::Raw("cli".into(), Some(1)),
             Segment::DoubleSub,
-            Segment::Raw(String::from("-abc")),
-            Segment::Raw(String::from("--long=abc")),
-            Segment::Raw(String::from("*&%asd")),
+            Segment::Raw("-abc".into(), Some(3)),
+            Segment::Raw("--long=abc".into(), Some(4)),
+            Segment::Raw("*&%asd".into(), Some(5)),
         ],
         segments,
     );
@@ -101,12 +101,87 @@ fn parse_test() {
     let segments = Segment::from_vec(args_os);
     assert_eq!(
         vec![
-            Segment::Raw(String::from("--long=")),
-            Segment::Raw(String::from("-abc-cd")),
-            Segment::Raw(String::from("--=")),
-            Segment::Raw(String::from("---")),
+            Segment::Raw("--long=".into(), Some(1)),
+            Segment::Raw("-abc-cd".into(), Some(2)),
+            Segment::Raw("--=".into(), Some(3)),
+            Segment::Raw("---".into(), Some(4)),
         ],
         segments,
     );
 }
 
+
+#[test]
+fn negative_numbers_test() {
+    use commander_rust_core::{Command, Options, NegativeNumbers};
+    use commander_rust_core::traits::PushOptions;
+
+    assert!(Segment::is_negative_number("-5"));
+    assert!(Segment::is_negative_number("-1.5"));
+    assert!(Segment::is_negative_number("-.5"));
+    assert!(Segment::is_negative_number("-1e3"));
+    assert!(!Segment::is_negative_number("-inf"));
+    assert!(!Segment::is_negative_number("-a1"));
+    assert!(!Segment::is_negative_number("--5"));
+    assert!(!Segment::is_negative_number("-"));
+
+    let mut cmd = Command::from("calc");
+    let args = |args: Vec<&str>| args.into_iter().map(OsString::from).collect::<Vec<OsString>>();
+
+    cmd.push_option(Options::new(Some(String::from("1")), String::from("one"), None));
+    cmd.push_option(Options::from("-o, --offset <offset>"));
+
+    // standard rules know nothing about definitions
+    assert_eq!(
+        Segment::from_vec(args(vec!["-5"])),
+        vec![Segment::Short(String::from("5"), vec![], Some(1))],
+    );
+    assert_eq!(
+        Segment::from_vec_for(&cmd, args(vec!["add", "-5", "-1.5", "-o", "-10"])),
+        vec![
+            Segment::Raw("add".into(), Some(1)),
+            Segment::Raw("-5".into(), Some(2)),
+            Segment::Raw("-1.5".into(), Some(3)),
+            Segment::Short(String::from("o"), vec![], Some(4)),
+            Segment::Raw("-10".into(), Some(5)),
+        ],
+    );
+    // `-1` and `-11` are defined short options
+    assert_eq!(
+        Segment::from_vec_for(&cmd, args(vec!["-11", "-12"])),
+        vec![
+            // short options of a cluster are at the same position
+            Segment::Short(String::from("1"), vec![], Some(1)),
+            Segment::Short(String::from("1"), vec![], Some(1)),
+            Segment::Raw("-12".into(), Some(2)),
+        ],
+    );
+
+    cmd.negative_numbers = NegativeNumbers::Values;
+    assert_eq!(Segment::from_vec_for(&cmd, args(vec!["-1"])), vec![Segment::Raw("-1".into(), Some(1))]);
+
+    cmd.negative_numbers = NegativeNumbers::Options;
+    assert_eq!(Segment::from_vec_for(&cmd, args(vec!["-5"])), vec![Segment::Short(String::from("5"), vec![], Some(1))]);
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_test() {
+    use std::os::unix::ffi::OsStringExt;
+
+    let bad = || OsString::from_vec(vec![b'a', 0xff]);
+    let mut long = b"--output=".to_vec();
+
+    long.extend(vec![b'a', 0xff]);
+
+    // they are values rather than options, except the value of `--long=value`
+    assert_eq!(
+        Segment::from_vec(vec![bad(), OsString::from_vec(long), OsString::from("--"), bad()]),
+        vec![
+            Segment::Raw(bad(), Some(1)),
+            Segment::Long(String::from("output"), vec![Segment::Raw(bad(), Some(2))], Some(2)),
+            Segment::DoubleSub,
+            Segment::Raw(bad(), Some(4)),
+        ],
+    );
+}
@@ -0,0 +1,121 @@
+use commander_rust_core::{ Argument, ArgumentType, Options, SubCommand, Command, RepeatPolicy, GroupKind, NegativeNumbers };
+use commander_rust_core::config::Config;
+use commander_rust_core::errors::SpecError;
+use commander_rust_core::spec::{ parse_argument, parse_arguments, parse_option, parse_sub_command, parse_command, check_names };
+use commander_rust_core::traits::{ GetArgs, GetOpts, PushOptions, PushSubCommand };
+
+#[test]
+fn argument_spec_test() {
+    let port = parse_argument(r#"[port = "8080"]"#).unwrap();
+
+    assert_eq!(port.ty, ArgumentType::OptionalSingle);
+    assert_eq!(port.default, Some(String::from("8080")));
+    assert_eq!(parse_argument(r#"[port] = "8080""#), Ok(port));
+    assert_eq!("<...files>".parse::<Argument>().unwrap().ty, ArgumentType::RequiredMultiple);
+
+    let args: Vec<String> = parse_arguments("<src> [dst] [..rest]").unwrap().iter().map(|arg| arg.to_string()).collect();
+
+    assert_eq!(args, vec!["<src>", "[dst]", "[..rest]"]);
+    assert_eq!(parse_arguments("[a] <b>").unwrap_err().position, 4);
+    assert_eq!(parse_arguments("<..a> <b>").unwrap_err().message, "only last argument could be multiply argument");
+    assert_eq!(parse_arguments("<a> [a]").unwrap_err().message, "arguments duplicate");
+    assert_eq!(parse_argument("<a"), Err(SpecError::new("<a", 2, "expected `>`")));
+}
+
+#[test]
+fn option_spec_test() {
+    let options: Options = r#"-r, --registry <url> [..mirrors], "registry to use", env = "REGISTRY", repeat = append"#.parse().unwrap();
+
+    assert_eq!(options.short, Some(String::from("r")));
+    assert_eq!(options.long, "registry");
+    assert_eq!(options.desc, Some(String::from("registry to use")));
+    assert_eq!(options.env, Some(String::from("REGISTRY")));
+    assert_eq!(options.repeat, RepeatPolicy::Append);
+    assert_eq!(options.get_args().len(), 2);
+
+    let options = parse_option(r#"--dry-run, required, conflicts_with = ["force", "dir"], exactly_one_of = "mode""#).unwrap();
+
+    assert_eq!(options.long, "dry-run");
+    assert!(options.required);
+    assert_eq!(options.conflicts_with, vec!["force", "dir"]);
+    assert_eq!(options.group.map(|group| (group.name, group.kind)), Some((String::from("mode"), GroupKind::ExactlyOne)));
+    assert!(parse_option("--[no-]color").unwrap().negatable);
+
+    let error = |spec: &str| parse_option(spec).unwrap_err();
+
+    assert_eq!(error("--[no-]color <c>").message, "negatable option can't accept arguments");
+    assert_eq!(error("-ab, --all").message, "short name of option should be one character");
+    assert_eq!(error(r#"--all, "desc", repeat = twice"#).position, 15);
+    assert_eq!(error(r#"--all, unknown"#).message, "unknown parameter of option");
+    assert_eq!(error(r#"--all, "desc"#).message, "unterminated string");
+    assert_eq!(
+        error("-a --all").to_string(),
+        "Invalid spec, expected `,` at position 3 of `\u{1b}[1m-a --all\u{1b}[0m`.",
+    );
+}
+
+#[test]
+fn command_spec_test() {
+    let sub_cmd: SubCommand = r#"install|i <pkg> [dir = "."] "install a package""#.parse().unwrap();
+
+    assert_eq!(sub_cmd.name, "install");
+    assert_eq!(sub_cmd.aliases, vec!["i"]);
+    assert_eq!(sub_cmd.desc, Some(String::from("install a package")));
+    assert_eq!(sub_cmd.get_args()[1].default, Some(String::from(".")));
+    assert!(parse_sub_command(r#"install <pkg>, parent = npm"#).is_err());
+    assert_eq!(
+        parse_sub_command(r#"install <pkg> = "a""#).unwrap_err().message,
+        "required argument of command or sub-command can't have default value",
+    );
+
+    let cmd: Command = r#""0.1.0", calc <..nums>, "calculator", negative_numbers = values"#.parse().unwrap();
+
+    assert_eq!(cmd.name, "calc");
+    assert_eq!(cmd.version, "0.1.0");
+    assert_eq!(cmd.desc, Some(String::from("calculator")));
+    assert_eq!(cmd.negative_numbers, NegativeNumbers::Values);
+    assert!(cmd.get_opts().is_empty());
+    assert!(parse_command("calc, negative_numbers = never").is_err());
+    assert_eq!(cmd.config, None);
+
+    let cmd = parse_command(r#"npms, "package manager", config_option = "config", config_file = "~/.npms.toml""#).unwrap();
+
+    assert_eq!(cmd.desc, Some(String::from("package manager")));
+    assert_eq!(cmd.config, Some(Config { option: Some(String::from("config")), path: Some(String::from("~/.npms.toml")) }));
+    assert_eq!(
+        parse_command(r#"npms, config_file = "a.toml""#).unwrap().config,
+        Some(Config { option: None, path: Some(String::from("a.toml")) }),
+    );
+    assert!(parse_command("npms, config_option = config").is_err());
+    assert!(!cmd.response_files);
+    assert!(parse_command(r#"cc <..files>, "compiler", response_files"#).unwrap().response_files);
+    assert!(parse_command("cc, response_files = true").is_err());
+    assert!(parse_command("calc extra").is_err());
+}
+
+#[test]
+fn constraint_names_test() {
+    let mut cmd = parse_command("pack [dir]").unwrap();
+    let mut send = parse_sub_command("send [message]").unwrap();
+
+    cmd.push_option(parse_option(r#"-z, --zip, conflicts_with = "dir""#).unwrap());
+    cmd.push_option(parse_option("--dry-run").unwrap());
+    send.push_option(parse_option(r#"-q, --quiet, conflicts_with = ["message", "dry-run"], requires = "urgent""#).unwrap());
+    send.push_option(parse_option("-u, --urgent").unwrap());
+    cmd.push_sub_command(send.clone());
+    assert_eq!(check_names(&cmd), Ok(()));
+
+    // arguments of sub-commands are unknown to the command
+    cmd.push_option(parse_option(r#"--level <n>, required_unless = ["zip", "message"]"#).unwrap());
+    assert_eq!(
+        check_names(&cmd),
+        Err(SpecError::new(r#"required_unless = ["zip", "message"]"#, 26, "unknown name, names should be long names of options (without `--`) or names of arguments")),
+    );
+
+    let mut cmd = parse_command("pack [dir]").unwrap();
+
+    cmd.push_option(parse_option("--dry-run").unwrap());
+    send.push_option(parse_option(r#"--force, requires = "urgnet""#).unwrap());
+    cmd.push_sub_command(send);
+    assert_eq!(check_names(&cmd).unwrap_err().spec, r#"requires = ["urgnet"]"#);
+}
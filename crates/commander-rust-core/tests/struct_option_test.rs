@@ -10,14 +10,20 @@ fn options_test() {
         Argument {
             name: String::from("a"),
             ty: ArgumentType::RequiredSingle,
+            default: None,
+            possible_values: None,
         },
         Argument {
             name: String::from("b"),
             ty: ArgumentType::OptionalSingle,
+            default: None,
+            possible_values: None,
         },
         Argument {
             name: String::from("c"),
             ty: ArgumentType::OptionalMultiple,
+            default: None,
+            possible_values: None,
         }
     ]);
     assert_eq!(option.desc, Some(String::from("hello world!")));
@@ -29,14 +35,20 @@ fn options_test() {
         Argument {
             name: String::from("a"),
             ty: ArgumentType::RequiredSingle,
+            default: None,
+            possible_values: None,
         },
         Argument {
             name: String::from("b"),
             ty: ArgumentType::OptionalSingle,
+            default: None,
+            possible_values: None,
         },
         Argument {
             name: String::from("c"),
             ty: ArgumentType::OptionalMultiple,
+            default: None,
+            possible_values: None,
         }
     ]);
     assert_eq!(option.desc, None);
@@ -48,6 +60,8 @@ fn options_test() {
         Argument {
             name: String::from("a"),
             ty: ArgumentType::RequiredSingle,
+            default: None,
+            possible_values: None,
         },
     ]);
     assert_eq!(option.desc, Some(String::from("hello world!")));
@@ -59,10 +73,14 @@ fn options_test() {
         Argument {
             name: String::from("ab"),
             ty: ArgumentType::RequiredSingle,
+            default: None,
+            possible_values: None,
         },
         Argument {
             name: String::from("cd"),
             ty: ArgumentType::OptionalSingle,
+            default: None,
+            possible_values: None,
         },
     ]);
     assert_eq!(option.desc, Some(String::from("hello world!")));
@@ -86,14 +104,20 @@ fn options_test() {
         Argument {
             name: String::from("a"),
             ty: ArgumentType::RequiredSingle,
+            default: None,
+            possible_values: None,
         },
         Argument {
             name: String::from("b"),
             ty: ArgumentType::OptionalSingle,
+            default: None,
+            possible_values: None,
         },
         Argument {
             name: String::from("c"),
             ty: ArgumentType::OptionalMultiple,
+            default: None,
+            possible_values: None,
         }
     ]);
     assert_eq!(option.desc, Some(String::from("hello world!")));
@@ -49,4 +49,35 @@ fn sub_command_test() {
     assert_eq!("sub".to_string(), sub_command.name);
     assert!(sub_command.get_args().is_empty());
     assert_eq!(None, sub_command.desc);
-}
\ No newline at end of file
+}
+
+#[test]
+fn sub_command_aliases_test() {
+    use commander_rust_core::Command;
+    use commander_rust_core::traits::PushSubCommand;
+    use commander_rust_core::parser::{SegmentWrapper, Segment};
+
+    let sub_command = SubCommand::from(r#"npms -> install|i|add <pkg>, "install a package""#);
+
+    assert_eq!("install".to_string(), sub_command.name);
+    assert_eq!(vec!["i".to_string(), "add".to_string()], sub_command.aliases);
+    assert!(sub_command.is_named("install"));
+    assert!(sub_command.is_named("i"));
+    assert!(!sub_command.is_named("in"));
+
+    let mut cmd = Command::from(r#"npms, "node package manager""#);
+
+    cmd.push_sub_command(sub_command);
+    assert_eq!(Some("install"), cmd.get_sub_cmd("add").map(|sub_cmd| sub_cmd.name.as_str()));
+    assert_eq!(Some("install"), cmd.find_sub_cmd("i").map(|sub_cmd| sub_cmd.name.as_str()));
+    assert!(cmd.get_sub_cmd("a").is_none());
+    assert_eq!(
+        "\u{1b}[1;3mDESCRIPTION\u{1b}[0m:\n    node package manager\n\n\u{1b}[1;3mUSAGE\u{1b}[0m:\n    npms [sub_commands]\n\n\n\u{1b}[1;3mSUB_COMMANDS\u{1b}[0m:\n    install, i, add    install a package\n\n",
+        format!("{}", cmd),
+    );
+
+    // aliases are replaced by the name of sub-command
+    let ((_, sub), _, _) = SegmentWrapper::parse_from(&cmd, vec!["npms", "i", "react"]).unwrap();
+
+    assert_eq!(Some(Segment::Command(Some("install".to_string()), vec![Segment::Raw("react".into(), Some(2))], Some(1))), sub);
+}
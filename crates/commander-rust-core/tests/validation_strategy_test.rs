@@ -47,9 +47,10 @@ fn validation_strategy_test() {
             Some(Segment::Command(
                 None,
                 vec![
-                    Segment::Raw("dimos".to_string()),
-                    Segment::Raw("123456".to_string()),
+                    Segment::Raw("dimos".into(), Some(1)),
+                    Segment::Raw("123456".into(), Some(2)),
                 ],
+                None,
             )),
             cmd
         );
@@ -57,13 +58,14 @@ fn validation_strategy_test() {
             Some(Segment::Command(
                 Some("search".to_string()),
                 vec![
-                    Segment::Raw("name".to_string()),
-                    Segment::Raw("age".to_string()),
-                    Segment::Raw("sex".to_string()),
-                    Segment::Raw("a".to_string()),
-                    Segment::Raw("b".to_string()),
-                    Segment::Raw("c".to_string()),
-                ]
+                    Segment::Raw("name".into(), Some(4)),
+                    Segment::Raw("age".into(), Some(5)),
+                    Segment::Raw("sex".into(), Some(6)),
+                    Segment::Raw("a".into(), Some(17)),
+                    Segment::Raw("b".into(), Some(18)),
+                    Segment::Raw("c".into(), Some(19)),
+                ],
+                Some(3),
             )),
             sub,
         );
@@ -71,17 +73,17 @@ fn validation_strategy_test() {
         assert_eq!(
             vec![
                 Segment::Long("name".to_string(), vec![
-                    Segment::Raw("Jack".to_string()),
-                    Segment::Raw("Rose".to_string()),
-                    Segment::Raw("Smith".to_string()),
-                ]),
+                    Segment::Raw("Jack".into(), Some(8)),
+                    Segment::Raw("Rose".into(), Some(9)),
+                    Segment::Raw("Smith".into(), Some(10)),
+                ], Some(7)),
                 Segment::Long("age".to_string(), vec![
-                    Segment::Raw("20".to_string()),
-                    Segment::Raw("40".to_string()),
-                ]),
+                    Segment::Raw("20".into(), Some(12)),
+                    Segment::Raw("40".into(), Some(13)),
+                ], Some(11)),
                 Segment::Long("sex".to_string(), vec![
-                    Segment::Raw("all".to_string()),
-                ])
+                    Segment::Raw("all".into(), Some(15)),
+                ], Some(14))
             ],
             local_opts,
         );
@@ -0,0 +1,230 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{ quote, format_ident };
+use syn::{ Data, DeriveInput, Fields, Lit, Meta, NestedMeta, LitStr, Type, PathArguments, GenericArgument };
+use syn::spanned::Spanned;
+use crate::errors::compile_error;
+use crate::errors::msg::{ DERIVE_FROM_ARG_ONLY_ENUM, DERIVE_FROM_ARG_ONLY_UNIT_VARIANT,
+                          DERIVE_FROM_ARG_NO_GENERICS, UNKNOWN_ARG_PARAMETER, ARG_VALUE_IS_NON_DUPLICATED,
+                          DERIVE_FROM_APP_ONLY_NAMED_STRUCT, DERIVE_FROM_APP_NO_GENERICS, UNKNOWN_OPT_PARAMETER };
+use crate::utils::{ import_raw_type, last_segment, to_kebab_case_from_snake,
+                    TOKEN_ARG, TOKEN_APPLICATION, TRAIT_FROM_ARG, TRAIT_FROM_ARGS, TRAIT_FROM_APP,
+                    TRAIT_FIELD_FROM_APP, TRAIT_FIELD_FROM_ARG, PATH_TRAITS, PATH_ERRORS, TOKEN_PARSE_ERROR };
+
+// e.g., `HttpServer` => `http-server`, `HTTPServer` => `http-server`
+fn to_kebab_case(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let mut result = String::new();
+
+    for (idx, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && idx > 0 {
+            let prev = chars[idx - 1];
+            let next_is_lower = chars.get(idx + 1).is_some_and(|next| next.is_lowercase());
+
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                result.push('-');
+            }
+        }
+
+        result.extend(c.to_lowercase());
+    }
+
+    result
+}
+
+// get `key` of `#[attr_name(key = "...")]`, e.g., `#[arg(name = "...")]`
+fn get_attr_value(attrs: &[syn::Attribute], attr_name: &str, key: &str, error: &str) -> Result<Option<LitStr>, TokenStream2> {
+    let mut value = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(attr_name)) {
+        match attr.parse_meta() {
+            Ok(Meta::List(list)) => {
+                for nested in list.nested.iter() {
+                    match nested {
+                        NestedMeta::Meta(Meta::NameValue(pair)) if pair.path.is_ident(key) => {
+                            if let Lit::Str(lit) = &pair.lit {
+                                value = Some(lit.clone());
+                            } else {
+                                return Err(compile_error(pair.lit.span(), error));
+                            }
+                        }
+                        _ => return Err(compile_error(nested.span(), error)),
+                    }
+                }
+            }
+            Ok(meta) => return Err(compile_error(meta.span(), error)),
+            Err(e) => return Err(e.to_compile_error()),
+        }
+    }
+
+    Ok(value)
+}
+
+// `Vec<T>`, `Option<Vec<T>>` and `Result<Vec<T>, E>` accept multiply arguments
+fn is_multiply_type(ty: &Type) -> bool {
+    match last_segment(ty) {
+        Some(segment) if segment.ident == "Vec" => true,
+        Some(segment) if segment.ident == "Option" || segment.ident == "Result" => {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                match args.args.first() {
+                    Some(GenericArgument::Type(inner)) => is_multiply_type(inner),
+                    _ => false,
+                }
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+// implement `FromArg` for enum whose variants are all unit variants
+// variants are matched by their names in kebab-case, or `#[arg(name = "...")]` if it's offered
+pub(crate) fn derive_from_arg(input: &DeriveInput) -> TokenStream2 {
+    let ident = &input.ident;
+    let data = if let Data::Enum(data) = &input.data {
+        data
+    } else {
+        return compile_error(ident.span(), DERIVE_FROM_ARG_ONLY_ENUM);
+    };
+
+    if !input.generics.params.is_empty() {
+        return compile_error(input.generics.span(), DERIVE_FROM_ARG_NO_GENERICS);
+    }
+
+    let mut values = vec![];
+    let mut variants = vec![];
+
+    for variant in data.variants.iter() {
+        if !matches!(variant.fields, Fields::Unit) {
+            return compile_error(variant.span(), DERIVE_FROM_ARG_ONLY_UNIT_VARIANT);
+        }
+
+        let value = match get_attr_value(&variant.attrs, "arg", "name", UNKNOWN_ARG_PARAMETER) {
+            Ok(Some(name)) => name.value(),
+            Ok(None) => to_kebab_case(&variant.ident.to_string()),
+            Err(e) => return e,
+        };
+
+        if values.contains(&value) {
+            return compile_error(variant.span(), ARG_VALUE_IS_NON_DUPLICATED);
+        }
+
+        values.push(value);
+        variants.push(&variant.ident);
+    }
+
+    let ty_arg = import_raw_type(vec![TOKEN_ARG]);
+    let trait_from_arg = import_raw_type(vec![PATH_TRAITS, TRAIT_FROM_ARG]);
+    let values_str = values.join(", ");
+
+    quote! {
+        impl<'a> #trait_from_arg<'a> for #ident {
+            type Error = String;
+
+            fn from_arg(arg: &'a #ty_arg) -> Result<Self, Self::Error> {
+                match arg.to_str() {
+                    #(Some(#values) => Ok(#ident::#variants),)*
+                    _ => Err(format!("invalid value `{}`, possible values are `{}`", arg, #values_str)),
+                }
+            }
+
+            fn possible_values() -> Option<Vec<&'static str>> {
+                Some(vec![#(#values),*])
+            }
+        }
+    }
+}
+
+// implement `FromApp` for struct whose fields are options, local options are found firstly, then global options
+// fields are options with the same names in kebab-case, or `#[opt(name = "...")]` if it's offered
+// 1. `bool` means whether the option is offered
+// 2. `T: FromArgs` (e.g., `Vec<T>`) is converted from all arguments of the option
+// 3. `T: FromApp` is converted by `FromApp::from_named`, e.g., `Count` is the times the option is offered
+// 4. other types should implement `FromArg`, they are converted from the first argument of the option
+// errors are `ParseError` whose token is the argument failed to be converted, or the option if it's unknown,
+// they report the environment variable or the config file if the value is read from it
+pub(crate) fn derive_from_app(input: &DeriveInput) -> TokenStream2 {
+    let ident = &input.ident;
+    let fields = if let Data::Struct(syn::DataStruct { fields: Fields::Named(fields), .. }) = &input.data {
+        fields
+    } else {
+        return compile_error(ident.span(), DERIVE_FROM_APP_ONLY_NAMED_STRUCT);
+    };
+
+    if !input.generics.params.is_empty() {
+        return compile_error(input.generics.span(), DERIVE_FROM_APP_NO_GENERICS);
+    }
+
+    let ty_app = import_raw_type(vec![TOKEN_APPLICATION]);
+    let trait_from_args = import_raw_type(vec![PATH_TRAITS, TRAIT_FROM_ARGS]);
+    let trait_from_app = import_raw_type(vec![PATH_TRAITS, TRAIT_FROM_APP]);
+    let path_traits = import_raw_type(vec![PATH_TRAITS]);
+    let trait_field_from_app = format_ident!("{}", TRAIT_FIELD_FROM_APP);
+    let trait_field_from_arg = format_ident!("{}", TRAIT_FIELD_FROM_ARG);
+    let ty_parse_error = import_raw_type(vec![PATH_ERRORS, TOKEN_PARSE_ERROR]);
+    let mut exprs = vec![];
+
+    for field in fields.named.iter() {
+        // named fields always have idents
+        let field_ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let long = match get_attr_value(&field.attrs, "opt", "name", UNKNOWN_OPT_PARAMETER) {
+            Ok(Some(name)) => name.value(),
+            Ok(None) => to_kebab_case_from_snake(&field_ident.to_string()),
+            Err(e) => return e,
+        };
+        let expr = if last_segment(ty).is_some_and(|segment| segment.ident == "bool") {
+            quote! { app.get_opt_args(#long).is_some() }
+        } else if is_multiply_type(ty) {
+            quote! {
+                {
+                    let result = match app.get_opt_args(#long) {
+                        Some(args) if !args.is_empty() => <#ty as #trait_from_args>::from_args(&args),
+                        _ => <#ty as #trait_from_args>::from_absent(),
+                    };
+
+                    // arguments are converted together, so the token is the option
+                    match (result, <#ty as #trait_from_args>::possible_values()) {
+                        (Ok(value), _) => value,
+                        (Err(_), Some(values)) => return Err(#ty_parse_error::InvalidValue {
+                            token: format!("--{}", #long),
+                            possible_values: values.into_iter().map(String::from).collect(),
+                            index: None,
+                        }.with_source(app.get_opt_source(#long))),
+                        (Err(_), None) => return Err(#ty_parse_error::ConversionFailed {
+                            token: format!("--{}", #long),
+                            ty: String::from(stringify!(#ty)),
+                            index: None,
+                        }.with_source(app.get_opt_source(#long))),
+                    }
+                }
+            }
+        } else {
+            quote! {
+                {
+                    use #path_traits::{ #trait_field_from_app, #trait_field_from_arg };
+
+                    (&&std::marker::PhantomData::<#ty>).convert_field(app, #long, stringify!(#ty))?
+                }
+            }
+        };
+
+        exprs.push(quote! { #field_ident: #expr });
+    }
+
+    quote! {
+        impl<'a> #trait_from_app<'a> for #ident {
+            type Error = #ty_parse_error;
+
+            fn from_app(app: &'a #ty_app) -> Result<Self, Self::Error> {
+                Ok(#ident {
+                    #(#exprs,)*
+                })
+            }
+
+            fn parse_error(err: Self::Error, _ty: &str) -> #ty_parse_error {
+                err
+            }
+        }
+    }
+}
@@ -11,11 +11,26 @@ pub mod msg {
     pub const MULTIPLY_ARGUMENT_IS_ONLY_LAST: &str = "only last argument could be multiply argument.";
     pub const ARGUMENT_IS_NON_DUPLICATED: &str = "arguments duplicate, define arguments with same name more than once.";
     pub const OPTION_IS_NON_DUPLICATED: &str = "option duplicate, define options with same name more than once.";
-    pub const SUB_CMD_IS_NON_DUPLICATED: &str = "sub-command duplicate, define sub-commands with same name more than once.";
-    pub const CMD_IS_ONLY: &str = "can only define #[command] once.";
-    pub const REGISTER_UNKNOWN_SUB_CMD: &str = "try to register unknown sub-command, it was not defined as sub-command using #[sub_command].";
-    pub const REGISTER_UNKNOWN_CMD: &str = "try to register an unknown command, it was not defined as command using #[command].";
+    pub const UNKNOWN_SUB_CMD_PARAMETER: &str = "unknown parameter of sub-command, only `parent = parent_fn_name` is accepted.";
+    pub const UNKNOWN_OPTION_PARAMETER: &str = "unknown parameter of option, only `env = \"ENV_NAME\"`, `repeat = policy`, `required`, `conflicts_with = names`, `requires = names`, `required_unless = names`, `exactly_one_of = \"group\"` and `at_least_one_of = \"group\"` are accepted.";
+    pub const NEGATABLE_OPTION_PREFIX: &str = "negatable option should be defined as `--[no-]long-name`.";
+    pub const NEGATABLE_OPTION_IS_FLAG: &str = "negatable option can't accept arguments, it should be a flag.";
+    pub const UNKNOWN_CMD_PARAMETER: &str = "unknown parameter of command, only `negative_numbers = value`, `config_option = \"long-name\"`, `config_file = \"path\"` and `response_files` are accepted.";
+    pub const UNKNOWN_NEGATIVE_NUMBERS: &str = "unknown value of `negative_numbers`, only `auto`, `values` and `options` are accepted.";
+    pub const UNKNOWN_REPEAT_POLICY: &str = "unknown repeat policy, only `last`, `first`, `append` and `error` are accepted.";
+    pub const ATTRIBUTE_WITHOUT_COMMAND: &str = "`#[option]`, `#[default_options]` and `#[completions]` should be used with `#[command]` or `#[sub_command]` of the same function.";
+    pub const COMPLETIONS_ONLY_FOR_COMMAND: &str = "`#[completions]` can only be used with `#[command]`.";
+    pub const UNKNOWN_CONSTRAINT_NAME: &str = "unknown name, names of `conflicts_with`, `requires` and `required_unless` should be long names of options (without `--`) or names of arguments of the command.";
     pub const UNUSED_ARGUMENT: &str = "unused argument.";
+    pub const REQUIRED_ARGUMENT_WITH_DEFAULT: &str = "required argument of command or sub-command can't have default value, use [optional argument] instead.";
+    pub const DERIVE_FROM_ARG_ONLY_ENUM: &str = "`#[derive(FromArg)]` can only be used on enums, implement `FromArg` by yourself for other types.";
+    pub const DERIVE_FROM_ARG_ONLY_UNIT_VARIANT: &str = "`#[derive(FromArg)]` only supports variants without fields.";
+    pub const DERIVE_FROM_ARG_NO_GENERICS: &str = "`#[derive(FromArg)]` doesn't support generic enums.";
+    pub const UNKNOWN_ARG_PARAMETER: &str = "unknown parameter of `#[arg]`, only `name = \"value\"` is accepted.";
+    pub const ARG_VALUE_IS_NON_DUPLICATED: &str = "value duplicates, variants are converted from the same value.";
+    pub const DERIVE_FROM_APP_ONLY_NAMED_STRUCT: &str = "`#[derive(FromApp)]` can only be used on structs with named fields.";
+    pub const DERIVE_FROM_APP_NO_GENERICS: &str = "`#[derive(FromApp)]` doesn't support generic structs.";
+    pub const UNKNOWN_OPT_PARAMETER: &str = "unknown parameter of `#[opt]`, only `name = \"long-name\"` is accepted.";
     // default options used
     pub const OPTION_VERSION_RESERVED: &str = "`-v` and `--version` are reserved keywords, remove them from your options";
     pub const OPTION_HELP_RESERVED: &str = "`-h` and `--help` are reserved keyword, remove them from your options";
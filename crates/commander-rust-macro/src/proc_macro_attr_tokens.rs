@@ -9,11 +9,36 @@ use std::collections::HashSet;
 use std::fmt;
 use crate::utils::{ import_raw_type, import_raw_trait };
 use crate::utils::{ TOKEN_ARGUMENT_TYPE, TOKEN_ARGUMENT,
-                    TOKEN_OPTIONS, TOKEN_SUB_COMMAND,
+                    TOKEN_OPTIONS, TOKEN_SUB_COMMAND, TOKEN_REPEAT_POLICY, TOKEN_NEGATIVE_NUMBERS, TOKEN_CONFIG, PATH_CONFIG,
+                    TOKEN_OPTION_GROUP, TOKEN_GROUP_KIND,
                     TRAIT_PUSH_ARGUMENT, TRAIT_PUSH_OPTIONS,
                     TOKEN_COMMAND, TRAIT_PUSH_SUB_COMMAND };
 use crate::errors::compile_error;
-use crate::errors::msg::{ MULTIPLY_ARGUMENT_IS_ONLY_LAST, ARGUMENTS_ORDER_ERROR, ARGUMENT_IS_NON_DUPLICATED };
+use crate::errors::msg::{ MULTIPLY_ARGUMENT_IS_ONLY_LAST, ARGUMENTS_ORDER_ERROR, ARGUMENT_IS_NON_DUPLICATED,
+                         UNKNOWN_SUB_CMD_PARAMETER, UNKNOWN_OPTION_PARAMETER, REQUIRED_ARGUMENT_WITH_DEFAULT,
+                         UNKNOWN_REPEAT_POLICY, UNKNOWN_CMD_PARAMETER, UNKNOWN_NEGATIVE_NUMBERS,
+                         NEGATABLE_OPTION_PREFIX, NEGATABLE_OPTION_IS_FLAG };
+
+// `repeat = policy` of options and variants of `RepeatPolicy`
+const REPEAT_POLICIES: [(&str, &str); 4] = [
+    ("last", "LastWins"),
+    ("first", "FirstWins"),
+    ("append", "Append"),
+    ("error", "Error"),
+];
+
+// `exactly_one_of = "group"` and `at_least_one_of = "group"` of options and variants of `GroupKind`
+const GROUP_KINDS: [(&str, &str); 2] = [
+    ("exactly_one_of", "ExactlyOne"),
+    ("at_least_one_of", "AtLeastOne"),
+];
+
+// `negative_numbers = value` of command and variants of `NegativeNumbers`
+const NEGATIVE_NUMBERS: [(&str, &str); 3] = [
+    ("auto", "Auto"),
+    ("values", "Values"),
+    ("options", "Options"),
+];
 
 #[derive(Debug, Clone)]
 pub(crate) enum ArgumentType {
@@ -126,6 +151,7 @@ impl ToTokens for Words {
 pub(crate) struct Argument {
     pub(crate) name: Ident,
     pub(crate) ty: ArgumentType,
+    pub(crate) default: Option<LitStr>,
 }
 
 impl fmt::Display for Argument {
@@ -143,13 +169,20 @@ impl fmt::Display for Argument {
 
 impl ToTokens for Argument {
     fn to_tokens(&self, stream: &mut TokenStream2) {
-        let Argument { name, ty } = self;
+        let Argument { name, ty, default } = self;
         let name = format!("{}", name);
         let argument_name = import_raw_type(vec![TOKEN_ARGUMENT]);
+        let default = if let Some(lit_str) = default {
+            quote! { Some(String::from(#lit_str)) }
+        } else {
+            quote! { None }
+        };
         let argument_expr = quote! {
             #argument_name {
                 name: String::from(#name),
                 ty: #ty,
+                default: #default,
+                possible_values: None,
             }
         };
 
@@ -157,6 +190,17 @@ impl ToTokens for Argument {
     }
 }
 
+// default value follows `=`, e.g., `= "8080"`
+fn parse_default(stream: ParseStream) -> Result<Option<LitStr>> {
+    if stream.peek(Token![=]) {
+        stream.parse::<Token![=]>()?;
+        Ok(Some(stream.parse::<LitStr>()?))
+    } else {
+        Ok(None)
+    }
+}
+
+// pattern: <a>, <..a>, [a], [..a], and `= "default"` could follow name or `>`, e.g., <a> = "0", [a = "0"]
 impl Parse for Argument {
     fn parse(stream: ParseStream) -> Result<Self> {
         if stream.peek(token::Lt) {
@@ -180,6 +224,7 @@ impl Parse for Argument {
             Ok(Argument {
                 name,
                 ty,
+                default: parse_default(stream)?,
             })
         } else if stream.peek(token::Bracket) {
             let content;
@@ -197,15 +242,18 @@ impl Parse for Argument {
             } else {
                 (content.parse::<Ident>()?, ArgumentType::OptionalSingle)
             };
+            let default = if content.is_empty() { parse_default(stream)? } else { parse_default(&content)? };
 
             Ok(Argument {
                 name,
                 ty,
+                default,
             })
         } else {
             Ok(Argument {
                 name: Ident::new("", Span2::call_site()),
                 ty: ArgumentType::OptionalSingle,
+                default: None,
             })
         }
     }
@@ -259,6 +307,17 @@ impl Parse for Arguments {
 }
 
 impl Arguments {
+    // required arguments of command or sub-command are always offered, so default values are useless
+    pub fn try_get_default_errors(&self) -> Option<TokenStream2> {
+        for arg in self.inner.iter() {
+            if arg.ty.is_required() && arg.default.is_some() {
+                return Some(compile_error(arg.name.span(), REQUIRED_ARGUMENT_WITH_DEFAULT));
+            }
+        }
+
+        None
+    }
+
     pub fn try_get_errors(&self) -> Option<TokenStream2> {
         let mut opt_start = false;
         let mut names = HashSet::new();
@@ -300,6 +359,40 @@ pub(crate) struct Options {
     pub(crate) long: Words,
     pub(crate) opt_args: Arguments,
     pub(crate) desc: Option<LitStr>,
+    // name of environment variable used if the option is not offered
+    pub(crate) env: Option<LitStr>,
+    // what to do if the option is offered more than once, one of `last`, `first`, `append` and `error`
+    pub(crate) repeat: Option<Ident>,
+    // `--[no-]long`, the negation `--no-long` is accepted too
+    pub(crate) negatable: bool,
+    // the option must be offered
+    pub(crate) required: bool,
+    // names of options or arguments, `conflicts_with = ["a", "b"]` or `conflicts_with = "a"`
+    pub(crate) conflicts_with: Vec<LitStr>,
+    pub(crate) requires: Vec<LitStr>,
+    pub(crate) required_unless: Vec<LitStr>,
+    // name of the group and its kind, e.g., `exactly_one_of = "format"`
+    pub(crate) group: Option<(LitStr, Ident)>,
+}
+
+// parse `["a", "b"]` or `"a"`
+fn parse_names(stream: ParseStream) -> Result<Vec<LitStr>> {
+    if stream.peek(token::Bracket) {
+        let content;
+        bracketed!(content in stream);
+
+        let names = content.parse_terminated::<LitStr, token::Comma>(|stream| stream.parse::<LitStr>())?;
+
+        Ok(names.into_iter().collect())
+    } else {
+        Ok(vec![stream.parse::<LitStr>()?])
+    }
+}
+
+fn fmt_names(names: &[LitStr]) -> String {
+    let names: Vec<String> = names.iter().map(|name| format!(r#""{}""#, name.value())).collect();
+
+    format!("[{}]", names.join(", "))
 }
 
 impl fmt::Display for Options {
@@ -308,7 +401,11 @@ impl fmt::Display for Options {
             write!(f, "{}", format!("-{}, ", short)).unwrap();
         }
 
-        write!(f, "--{}", self.long).unwrap();
+        if self.negatable {
+            write!(f, "--[no-]{}", self.long).unwrap();
+        } else {
+            write!(f, "--{}", self.long).unwrap();
+        }
 
         if !self.opt_args.inner.is_empty() {
             write!(f, " {}", self.opt_args).unwrap();
@@ -318,16 +415,53 @@ impl fmt::Display for Options {
             write!(f, r#", "{}""#, desc.value()).unwrap();
         }
 
+        if let Some(env) = &self.env {
+            write!(f, r#", env = "{}""#, env.value()).unwrap();
+        }
+
+        if let Some(repeat) = &self.repeat {
+            write!(f, ", repeat = {}", repeat).unwrap();
+        }
+
+        if self.required {
+            write!(f, ", required").unwrap();
+        }
+
+        if !self.conflicts_with.is_empty() {
+            write!(f, ", conflicts_with = {}", fmt_names(&self.conflicts_with)).unwrap();
+        }
+
+        if !self.requires.is_empty() {
+            write!(f, ", requires = {}", fmt_names(&self.requires)).unwrap();
+        }
+
+        if !self.required_unless.is_empty() {
+            write!(f, ", required_unless = {}", fmt_names(&self.required_unless)).unwrap();
+        }
+
+        if let Some((group, kind)) = &self.group {
+            write!(f, r#", {} = "{}""#, kind, group.value()).unwrap();
+        }
+
         write!(f, "")
     }
 }
 
+// pattern: [-s,] --[[no-]]long [<a> [b]], ["description"], [env = "ENV_NAME"], [repeat = last|first|append|error], [required],
+// [conflicts_with = names], [requires = names], [required_unless = names], [exactly_one_of|at_least_one_of = "group"]
 impl Parse for Options {
     fn parse(stream: ParseStream) -> Result<Self> {
         let short;
         let long;
         let opt_args;
-        let desc;
+        let mut desc = None;
+        let mut env = None;
+        let mut repeat = None;
+        let mut required = false;
+        let mut conflicts_with = vec![];
+        let mut requires = vec![];
+        let mut required_unless = vec![];
+        let mut group = None;
 
         // parse -s
         short = if stream.peek(Token![-]) && !stream.peek2(Token![-]) {
@@ -341,20 +475,75 @@ impl Parse for Options {
         };
 
 
-        // parse --long-option
+        // parse --long-option or --[no-]long-option
         stream.parse::<Token![-]>()?;
         stream.parse::<Token![-]>()?;
+
+        let negatable = if stream.peek(token::Bracket) {
+            let content;
+            bracketed!(content in stream);
+
+            let no = content.parse::<Ident>()?;
+
+            if no != "no" {
+                return Err(syn::Error::new(no.span(), NEGATABLE_OPTION_PREFIX));
+            }
+
+            content.parse::<Token![-]>()?;
+            true
+        } else {
+            false
+        };
+
         long = stream.parse::<Words>()?;
 
         // parse arguments <a> <b> and more
         opt_args = stream.parse::<Arguments>()?;
 
-        // parse description if it exists
-        if stream.peek(token::Comma) && stream.peek2(LitStr) {
+        if negatable && !opt_args.inner.is_empty() {
+            return Err(syn::Error::new(opt_args.inner[0].name.span(), NEGATABLE_OPTION_IS_FLAG));
+        }
+
+        // parse description, `env = "ENV_NAME"`, `repeat = policy`, `required` and constraints if they exist
+        while stream.peek(token::Comma) {
             stream.parse::<token::Comma>()?;
-            desc = stream.parse()?;
-        } else {
-            desc = None;
+
+            if desc.is_none() && stream.peek(LitStr) {
+                desc = Some(stream.parse::<LitStr>()?);
+            } else {
+                let key = stream.parse::<Ident>()?;
+
+                if key == "env" {
+                    stream.parse::<Token![=]>()?;
+                    env = Some(stream.parse::<LitStr>()?);
+                } else if key == "repeat" {
+                    stream.parse::<Token![=]>()?;
+
+                    let policy = stream.parse::<Ident>()?;
+
+                    if !REPEAT_POLICIES.iter().any(|(name, _)| policy == name) {
+                        return Err(syn::Error::new(policy.span(), UNKNOWN_REPEAT_POLICY));
+                    }
+
+                    repeat = Some(policy);
+                } else if key == "required" {
+                    required = true;
+                } else if key == "conflicts_with" {
+                    stream.parse::<Token![=]>()?;
+                    conflicts_with = parse_names(stream)?;
+                } else if key == "requires" {
+                    stream.parse::<Token![=]>()?;
+                    requires = parse_names(stream)?;
+                } else if key == "required_unless" {
+                    stream.parse::<Token![=]>()?;
+                    required_unless = parse_names(stream)?;
+                } else if GROUP_KINDS.iter().any(|(name, _)| key == name) {
+                    stream.parse::<Token![=]>()?;
+                    group = Some((stream.parse::<LitStr>()?, key));
+                } else {
+                    return Err(syn::Error::new(key.span(), UNKNOWN_OPTION_PARAMETER));
+                }
+            }
         }
 
         Ok(Options {
@@ -362,13 +551,24 @@ impl Parse for Options {
             long,
             opt_args,
             desc,
+            env,
+            repeat,
+            negatable,
+            required,
+            conflicts_with,
+            requires,
+            required_unless,
+            group,
         })
     }
 }
 
 impl ToTokens for Options {
     fn to_tokens(&self, stream: &mut TokenStream2) {
-        let Options { short, long, opt_args, desc } = self;
+        let Options {
+            short, long, opt_args, desc, env, repeat, negatable, required,
+            conflicts_with, requires, required_unless, group,
+        } = self;
         let opt_args = &opt_args.inner;
         let short = if let Some(tmp) = short {
             let tmp = format!("{}", tmp);
@@ -384,6 +584,25 @@ impl ToTokens for Options {
         } else {
             quote! { None }
         };
+        let env = if let Some(lit_str) = env {
+            quote! { Some(String::from(#lit_str)) }
+        } else {
+            quote! { None }
+        };
+        let repeat = repeat
+            .as_ref()
+            .and_then(|repeat| REPEAT_POLICIES.iter().find(|(name, _)| repeat == name))
+            .map_or("LastWins", |(_, variant)| variant);
+        let repeat = import_raw_type(vec![TOKEN_REPEAT_POLICY, repeat]);
+        let group = if let Some((name, kind)) = group {
+            let group_name = import_raw_type(vec![TOKEN_OPTION_GROUP]);
+            let kind = GROUP_KINDS.iter().find(|(key, _)| kind == key).map_or("ExactlyOne", |(_, variant)| variant);
+            let kind = import_raw_type(vec![TOKEN_GROUP_KIND, kind]);
+
+            quote! { Some(#group_name { name: String::from(#name), kind: #kind }) }
+        } else {
+            quote! { None }
+        };
         let options_expr = quote! {
             {
                 // `commander_rust::traits::PushArgument` needed
@@ -393,6 +612,14 @@ impl ToTokens for Options {
                     String::from(#long),
                     #description
                 );
+                options.env = #env;
+                options.repeat = #repeat;
+                options.negatable = #negatable;
+                options.required = #required;
+                options.conflicts_with = vec![#(String::from(#conflicts_with)),*];
+                options.requires = vec![#(String::from(#requires)),*];
+                options.required_unless = vec![#(String::from(#required_unless)),*];
+                options.group = #group;
                 #(options.push_argument(#opt_args);)*
                 options
             }
@@ -407,15 +634,23 @@ impl ToTokens for Options {
 pub(crate) struct SubCommand {
     pub(crate) belong: Option<Ident>,
     pub(crate) name: Ident,
+    // other names follow `|`, e.g., `install|i`
+    pub(crate) aliases: Vec<Ident>,
     pub(crate) cmd_args: Arguments,
     pub(crate) options: Vec<Options>,
     pub(crate) desc: Option<LitStr>,
+    // fn name of the parent sub-command if it's nested
+    pub(crate) parent: Option<Ident>,
 }
 
 impl fmt::Display for SubCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", format!("{}", self.name)).unwrap();
 
+        for alias in self.aliases.iter() {
+            write!(f, "|{}", alias).unwrap();
+        }
+
         if let Some(desc) = &self.desc {
             write!(f, r#", "{}""#, desc.value()).unwrap();
         }
@@ -424,26 +659,50 @@ impl fmt::Display for SubCommand {
     }
 }
 
-// pattern: name [<a> <b> [c] [..d]], ["description"]
+// pattern: name[|alias] [<a> <b> [c] [..d]], ["description"], [parent = parent_fn_name]
 impl Parse for SubCommand {
     fn parse(stream: ParseStream) -> Result<Self> {
         let name = stream.parse::<Ident>()?;
+        let mut aliases = vec![];
+
+        while stream.peek(Token![|]) {
+            stream.parse::<Token![|]>()?;
+            aliases.push(stream.parse::<Ident>()?);
+        }
+
         let cmd_args = stream.parse::<Arguments>()?;
-        let desc = if stream.peek(token::Comma) {
-            stream.parse::<token::Comma>()?;
-            Some(stream.parse::<LitStr>()?)
-        } else if stream.peek(LitStr) {
+        let mut desc = if stream.peek(LitStr) {
             Some(stream.parse::<LitStr>()?)
         } else {
             None
         };
+        let mut parent = None;
+
+        while stream.peek(token::Comma) {
+            stream.parse::<token::Comma>()?;
+
+            if desc.is_none() && stream.peek(LitStr) {
+                desc = Some(stream.parse::<LitStr>()?);
+            } else {
+                let key = stream.parse::<Ident>()?;
+
+                if key == "parent" {
+                    stream.parse::<Token![=]>()?;
+                    parent = Some(stream.parse::<Ident>()?);
+                } else {
+                    return Err(syn::Error::new(key.span(), UNKNOWN_SUB_CMD_PARAMETER));
+                }
+            }
+        }
 
         Ok(SubCommand {
             belong: None,
             name,
+            aliases,
             cmd_args,
             options: vec![],
             desc,
+            parent,
         })
     }
 }
@@ -453,9 +712,11 @@ impl ToTokens for SubCommand {
         let SubCommand {
             belong,
             name,
+            aliases,
             cmd_args,
             options,
             desc,
+            ..
         } = self;
         let belong = if let Some(belong) = belong {
             belong.to_string()
@@ -463,6 +724,7 @@ impl ToTokens for SubCommand {
             String::new()
         };
         let name = format!("{}", name);
+        let aliases: Vec<String> = aliases.iter().map(|alias| alias.to_string()).collect();
         let cmd_args = &cmd_args.inner;
         let cmd_name = import_raw_type(vec![TOKEN_SUB_COMMAND]);
         let desc = if let Some(lit_str) = desc {
@@ -482,6 +744,7 @@ impl ToTokens for SubCommand {
                     String::from(#name),
                     #desc
                 );
+                sub_cmd.aliases = vec![#(String::from(#aliases)),*];
                 #(sub_cmd.push_argument(#cmd_args);)*
                 #(sub_cmd.push_option(#options);)*
                 sub_cmd
@@ -500,8 +763,18 @@ pub(crate) struct Command {
     pub(crate) options: Vec<Options>,
     pub(crate) desc: Option<LitStr>,
     pub(crate) version: Option<LitStr>,
+    // how to parse negative numbers, one of `auto`, `values` and `options`
+    pub(crate) negative_numbers: Option<Ident>,
+    // long name of the option whose argument is the path of config file, e.g., `config` of `--config <path>`
+    pub(crate) config_option: Option<LitStr>,
+    // default location of config file
+    pub(crate) config_file: Option<LitStr>,
+    // whether `@file` is expanded into arguments read from the file
+    pub(crate) response_files: bool,
 }
 
+// pattern: ["version", ]name [<a> <b> [c] [..d]][, "description"][, negative_numbers = auto|values|options]
+// [, config_option = "long-name"][, config_file = "path"][, response_files]
 impl Parse for Command {
     fn parse(stream: ParseStream) -> Result<Self> {
         let mut version = None;
@@ -511,12 +784,43 @@ impl Parse for Command {
         }
         let name = stream.parse::<Ident>()?;
         let cmd_args = stream.parse::<Arguments>()?;
-        let desc = if stream.peek(token::Comma) {
+        let mut desc = None;
+        let mut negative_numbers = None;
+        let mut config_option = None;
+        let mut config_file = None;
+        let mut response_files = false;
+
+        while stream.peek(token::Comma) {
             stream.parse::<token::Comma>()?;
-            Some(stream.parse::<LitStr>()?)
-        } else {
-            None
-        };
+
+            if desc.is_none() && stream.peek(LitStr) {
+                desc = Some(stream.parse::<LitStr>()?);
+            } else {
+                let key = stream.parse::<Ident>()?;
+
+                if key == "negative_numbers" {
+                    stream.parse::<Token![=]>()?;
+
+                    let value = stream.parse::<Ident>()?;
+
+                    if !NEGATIVE_NUMBERS.iter().any(|(name, _)| value == name) {
+                        return Err(syn::Error::new(value.span(), UNKNOWN_NEGATIVE_NUMBERS));
+                    }
+
+                    negative_numbers = Some(value);
+                } else if key == "config_option" {
+                    stream.parse::<Token![=]>()?;
+                    config_option = Some(stream.parse::<LitStr>()?);
+                } else if key == "config_file" {
+                    stream.parse::<Token![=]>()?;
+                    config_file = Some(stream.parse::<LitStr>()?);
+                } else if key == "response_files" {
+                    response_files = true;
+                } else {
+                    return Err(syn::Error::new(key.span(), UNKNOWN_CMD_PARAMETER));
+                }
+            }
+        }
 
         Ok(Command {
             name,
@@ -525,6 +829,10 @@ impl Parse for Command {
             options: vec![],
             desc,
             version,
+            negative_numbers,
+            config_option,
+            config_file,
+            response_files,
         })
     }
 }
@@ -538,8 +846,17 @@ impl ToTokens for Command {
             options,
             desc,
             version,
+            negative_numbers,
+            config_option,
+            config_file,
+            response_files,
         } = self;
         let cmd_name = name.to_string();
+        let negative_numbers = negative_numbers
+            .as_ref()
+            .and_then(|value| NEGATIVE_NUMBERS.iter().find(|(name, _)| value == name))
+            .map_or("Auto", |(_, variant)| variant);
+        let negative_numbers = import_raw_type(vec![TOKEN_NEGATIVE_NUMBERS, negative_numbers]);
         let cmd_args = &cmd_args.inner;
         let desc = if let Some(desc) = desc {
             quote! { Some(String::from(#desc)) }
@@ -547,6 +864,17 @@ impl ToTokens for Command {
         let version = if let Some(ver) = version {
             quote! { Some(String::from(#ver)) }
         } else { quote! { None } };
+        let config = if config_option.is_some() || config_file.is_some() {
+            let ty_config = import_raw_type(vec![PATH_CONFIG, TOKEN_CONFIG]);
+            let option = if let Some(option) = config_option {
+                quote! { Some(String::from(#option)) }
+            } else { quote! { None } };
+            let path = if let Some(path) = config_file {
+                quote! { Some(String::from(#path)) }
+            } else { quote! { None } };
+
+            quote! { cmd.config = Some(#ty_config { option: #option, path: #path }); }
+        } else { quote! {} };
         let hidden_output = import_raw_type(vec![TOKEN_COMMAND]);
         let traits_needed = vec![
             import_raw_trait(TRAIT_PUSH_ARGUMENT),
@@ -562,6 +890,10 @@ impl ToTokens for Command {
                     cmd.version = ver;
                 }
 
+                cmd.negative_numbers = #negative_numbers;
+                cmd.response_files = #response_files;
+                #config
+
                 #(cmd.push_sub_command(#sub_cmds);)*
                 #(cmd.push_argument(#cmd_args);)*
                 #(cmd.push_option(#options);)*
@@ -1,4 +1,4 @@
-use syn::{ Ident, token, bracketed, LitStr };
+use syn::{ Ident, token, bracketed, LitStr, Expr };
 use syn::parse::{ Parse, ParseStream, Result };
 use quote::ToTokens;
 use quote::{ quote };
@@ -31,8 +31,11 @@ impl Parse for SubFnsList {
 pub(crate) struct Register {
     pub(crate) cmd: Ident,
     pub(crate) sub_fns_list: SubFnsList,
+    // if it's None, `std::env::args_os()` will be used
+    pub(crate) argv: Option<Expr>,
 }
 
+// pattern: cmd_fn, [sub_fn1, sub_fn2, ...][, argv]
 impl Parse for Register {
     fn parse(stream: ParseStream) -> Result<Self> {
         let cmd = stream.parse::<Ident>()?;
@@ -41,10 +44,18 @@ impl Parse for Register {
         stream.parse::<token::Comma>()?;
         bracketed!(content in stream);
         let sub_fns_list = content.parse::<SubFnsList>()?;
+        let argv = if stream.peek(token::Comma) {
+            stream.parse::<token::Comma>()?;
+
+            if stream.is_empty() { None } else { Some(stream.parse::<Expr>()?) }
+        } else {
+            None
+        };
 
         Ok(Register {
             cmd,
             sub_fns_list,
+            argv,
         })
     }
 }
@@ -0,0 +1,175 @@
+use syn::{ ItemFn, Attribute, Ident, LitStr };
+use syn::spanned::Spanned;
+use proc_macro2::{ TokenStream as TokenStream2, Span as Span2 };
+use quote::{ quote, format_ident };
+use crate::proc_macro_attr_tokens::{ Options, Words, Arguments };
+use crate::errors::compile_error;
+use crate::errors::msg::{ OPTION_IS_NON_DUPLICATED, OPTION_HELP_RESERVED, OPTION_VERSION_RESERVED,
+                          ATTRIBUTE_WITHOUT_COMMAND };
+use crate::utils::decorate_raw_idents;
+
+pub(crate) const ATTR_OPTION: &str = "option";
+pub(crate) const ATTR_DEFAULT_OPTIONS: &str = "default_options";
+pub(crate) const ATTR_COMPLETIONS: &str = "completions";
+const ATTR_COMMAND: &str = "command";
+const ATTR_SUB_COMMAND: &str = "sub_command";
+
+// attributes handled by `#[command]` or `#[sub_command]` of the same function
+pub(crate) enum Sibling {
+    Option(Box<Options>),
+    DefaultOptions,
+    Completions,
+}
+
+// prefix of attributes which are expanded above `#[command]` and moved below it, see `hoist`
+const HOISTED_PREFIX: &str = "_commander_rust_hoisted_";
+
+// the last segment of path of attribute, e.g., `option` of `#[commander_rust::option(..)]`,
+// the prefix of hoisted attributes is removed
+fn attr_name(attr: &Attribute) -> Option<String> {
+    attr.path.segments.last().map(|segment| segment.ident.to_string().trim_start_matches(HOISTED_PREFIX).to_string())
+}
+
+fn is_cmd_attr(attr: &Attribute) -> bool {
+    matches!(attr_name(attr).as_deref(), Some(ATTR_COMMAND) | Some(ATTR_SUB_COMMAND))
+}
+
+fn is_hoisted(attr: &Attribute) -> bool {
+    attr.path.segments.last().is_some_and(|segment| segment.ident.to_string().starts_with(HOISTED_PREFIX))
+}
+
+// `#[option]`, `#[default_options]` and `#[completions]` above `#[command]` (or `#[sub_command]`) are expanded first,
+// each of them is moved below `#[command]` (after the ones moved before), so that `#[command]` collects all of them in order.
+// `name` is the name of attribute, and `args` are its arguments (with parentheses) if it has.
+pub(crate) fn hoist(name: &str, args: TokenStream2, mut func: ItemFn) -> TokenStream2 {
+    match func.attrs.iter().position(is_cmd_attr) {
+        Some(idx) => {
+            let moved = func.attrs[(idx + 1)..].iter().take_while(|attr| is_hoisted(attr)).count();
+            let mut attrs = std::mem::take(&mut func.attrs);
+            let hoisted = format_ident!("{}{}", HOISTED_PREFIX, name);
+            let tail = attrs.split_off(idx + 1 + moved);
+
+            quote! {
+                #(#attrs)*
+                #[#hoisted #args]
+                #(#tail)*
+                #func
+            }
+        }
+        None => {
+            let error = compile_error(Span2::call_site(), ATTRIBUTE_WITHOUT_COMMAND);
+
+            quote! {
+                #error
+                #func
+            }
+        }
+    }
+}
+
+// remove attributes handled by `#[command]` (or `#[sub_command]`) from `func`, errors are returned if they are invalid
+pub(crate) fn take_siblings(func: &mut ItemFn) -> (Vec<(Span2, Sibling)>, Vec<TokenStream2>) {
+    let mut siblings = vec![];
+    let mut errors = vec![];
+
+    func.attrs.retain(|attr| {
+        let sibling = match attr_name(attr).as_deref() {
+            Some(ATTR_OPTION) => match attr.parse_args::<Options>() {
+                Ok(options) => Sibling::Option(Box::new(options)),
+                Err(e) => {
+                    errors.push(e.to_compile_error());
+                    return false;
+                }
+            },
+            Some(ATTR_DEFAULT_OPTIONS) => Sibling::DefaultOptions,
+            Some(ATTR_COMPLETIONS) => Sibling::Completions,
+            _ => return true,
+        };
+
+        siblings.push((attr.span(), sibling));
+        false
+    });
+
+    (siblings, errors)
+}
+
+fn default_options() -> Vec<(Options, &'static str)> {
+    let default_opts = vec![
+        ("version", "v", "print version information", OPTION_VERSION_RESERVED),
+        ("help", "h", "print help information", OPTION_HELP_RESERVED),
+    ];
+
+    default_opts
+        .into_iter()
+        .map(|(long, short, desc, error)| (Options {
+            short: Some(Ident::new(short, Span2::call_site())),
+            long: Words { inner: vec![Ident::new(long, Span2::call_site())] },
+            opt_args: Arguments { inner: vec![] },
+            desc: Some(LitStr::new(desc, Span2::call_site())),
+            env: None,
+            repeat: None,
+            negatable: false,
+            required: false,
+            conflicts_with: vec![],
+            requires: vec![],
+            required_unless: vec![],
+            group: None,
+        }, error))
+        .collect()
+}
+
+// options of function `func_name` in order, with names of hidden fns which return them.
+// `#[default_options]` is expanded into `--version` and `--help`.
+pub(crate) fn collect_options(func_name: &str, siblings: Vec<(Span2, Sibling)>, errors: &mut Vec<TokenStream2>) -> Vec<(Ident, Options)> {
+    let mut options = vec![];
+    let mut longs = vec![];
+    let mut shorts = vec![];
+
+    for (span, sibling) in siblings {
+        let opts = match sibling {
+            Sibling::Option(opts) => {
+                errors.extend(opts.opt_args.try_get_errors());
+                vec![(*opts, OPTION_IS_NON_DUPLICATED)]
+            }
+            Sibling::DefaultOptions => default_options(),
+            Sibling::Completions => continue,
+        };
+
+        for (opts, error) in opts {
+            let short = if let Some(short) = &opts.short { short.to_string() } else { String::new() };
+            let long = opts.long.to_string().replace('-', "_");
+
+            if longs.contains(&long) || (!short.is_empty() && shorts.contains(&short)) {
+                // redefined option (short or long) of the option
+                errors.push(compile_error(span, error));
+                break;
+            }
+
+            // pattern is: `_command_rust_prefix_` + `${fn_name}` + `${option_long_name}` + '_commander_rust_suffix_'.
+            options.push((decorate_raw_idents(vec![func_name, long.as_str()]), opts));
+            longs.push(long);
+            shorts.push(short);
+        }
+    }
+
+    options
+}
+
+// names of `conflicts_with`, `requires` and `required_unless` which are neither options nor arguments of the function (in order, without duplicates).
+// They are errors of `#[command]`, but options of command for `#[sub_command]`, which are checked by `execute!`.
+pub(crate) fn unknown_constraint_names(options: &[(Ident, Options)], args: &Arguments) -> Vec<LitStr> {
+    let mut names: Vec<String> = options.iter().map(|(_, opts)| opts.long.to_string()).collect();
+    let mut unknown: Vec<LitStr> = vec![];
+
+    names.extend(args.inner.iter().map(|arg| arg.name.to_string()));
+
+    for (_, opts) in options {
+        for name in opts.conflicts_with.iter().chain(opts.requires.iter()).chain(opts.required_unless.iter()) {
+            if !names.contains(&name.value()) && !unknown.iter().any(|prev| prev.value() == name.value()) {
+                unknown.push(name.clone());
+            }
+        }
+    }
+
+    unknown
+}
@@ -1,4 +1,4 @@
-use syn::{Ident, FnArg, token};
+use syn::{Ident, FnArg, Type, token};
 use proc_macro2::{TokenStream as TokenStream2, Span as Span2};
 use quote::{quote, format_ident, quote_spanned};
 use std::fmt::Display;
@@ -15,6 +15,10 @@ const TOKEN_SUFFIX: &str = "_commander_rust_suffix_";
 pub(crate) const TOKEN_ARGUMENT_TYPE: &str = "ArgumentType";
 pub(crate) const TOKEN_ARGUMENT: &str = "Argument";
 pub(crate) const TOKEN_OPTIONS: &str = "Options";
+pub(crate) const TOKEN_REPEAT_POLICY: &str = "RepeatPolicy";
+pub(crate) const TOKEN_NEGATIVE_NUMBERS: &str = "NegativeNumbers";
+pub(crate) const TOKEN_OPTION_GROUP: &str = "OptionGroup";
+pub(crate) const TOKEN_GROUP_KIND: &str = "GroupKind";
 pub(crate) const TOKEN_SUB_COMMAND: &str = "SubCommand";
 pub(crate) const TOKEN_COMMAND: &str = "Command";
 pub(crate) const TOKEN_SEGMENT: &str = "Segment";
@@ -24,15 +28,31 @@ pub(crate) const TOKEN_ARG: &str = "Arg";
 pub(crate) const TOKEN_ARGS: &str = "Args";
 pub(crate) const TOKEN_MIXED: &str = "Mixed";
 pub(crate) const TOKEN_TERMINATOR_KIND: &str = "TerminatorKind";
+pub(crate) const TOKEN_PARSE_ERROR: &str = "ParseError";
+pub(crate) const TOKEN_CONFIG: &str = "Config";
 pub(crate) const TRAIT_PUSH_ARGUMENT: &str = "PushArgument";
 pub(crate) const TRAIT_PUSH_OPTIONS: &str = "PushOptions";
 pub(crate) const TRAIT_PUSH_SUB_COMMAND: &str = "PushSubCommand";
 pub(crate) const TRAIT_FROM_ARG: &str = "FromArg";
 pub(crate) const TRAIT_FROM_ARGS: &str = "FromArgs";
 pub(crate) const TRAIT_FROM_APP: &str = "FromApp";
+pub(crate) const TRAIT_FIELD_FROM_APP: &str = "FieldFromApp";
+pub(crate) const TRAIT_FIELD_FROM_ARG: &str = "FieldFromArg";
+pub(crate) const TRAIT_INTO_EXIT_CODE: &str = "IntoExitCode";
+pub(crate) const TRAIT_SET_POSSIBLE_VALUES: &str = "SetPossibleValues";
 pub(crate) const PATH_PARSER: &str = "parser";
 pub(crate) const PATH_TRAITS: &str = "traits";
+pub(crate) const PATH_ERRORS: &str = "errors";
+pub(crate) const PATH_CONFIG: &str = "config";
+pub(crate) const PATH_REGISTER: &str = "register";
+pub(crate) const TOKEN_CMD_INFO: &str = "CmdInfo";
+pub(crate) const TOKEN_SUB_INFO: &str = "SubInfo";
+pub(crate) const TOKEN_REGISTRATION: &str = "Registration";
+pub(crate) const FN_CHECK: &str = "check";
+pub(crate) const FN_ASSEMBLE: &str = "assemble";
 pub(crate) const FN_CALL_EXTRA_TOKEN: &str = "extra_token";
+pub(crate) const FN_INFO_EXTRA_TOKEN: &str = "register_info";
+pub(crate) const FN_REPORT_ERROR: &str = "report_error";
 
 pub(crate) fn decorate_ident(source: Ident) -> Ident {
     format_ident!("{}{}{}", TOKEN_PREFIX, source, TOKEN_SUFFIX)
@@ -73,6 +93,20 @@ pub(crate) fn import_raw_trait<T: Display>(source: T) -> TokenStream2 {
     quote! { use commander_rust::traits::#ident }
 }
 
+// e.g., `dry_run` => `dry-run`
+pub(crate) fn to_kebab_case_from_snake(s: &str) -> String {
+    s.trim_start_matches("r#").replace('_', "-")
+}
+
+// the last segment of path of type, e.g., `Vec` of `std::vec::Vec<T>`
+pub(crate) fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    if let Type::Path(path) = ty {
+        path.path.segments.last()
+    } else {
+        None
+    }
+}
+
 //
 pub(crate) fn get_inputs_runtime_asserts(inputs: &Punctuated<FnArg, token::Comma>, def_args: &Arguments) -> TokenStream2 {
     let mut args_map = HashMap::new();
@@ -112,7 +146,7 @@ pub(crate) fn get_inputs_runtime_asserts(inputs: &Punctuated<FnArg, token::Comma
                     } else {
                         exprs.push(quote_spanned! {span=>
                             {
-                                let arg = #ty_arg(String::new());
+                                let arg = #ty_arg::default();
                                 <#ty as #trait_from_arg>::from_arg(&arg);
                             }
                         });
@@ -156,12 +190,11 @@ pub(crate) fn generate_inputs(
 ) -> TokenStream2 {
     let mut args_map = HashMap::new();
     let mut really_inputs = vec![];
-    let ty_arg = import_raw_type(vec![TOKEN_ARG]);
-    let ty_args = import_raw_type(vec![TOKEN_ARGS]);
     let ty_mixed = import_raw_type(vec![TOKEN_MIXED]);
     let trait_from_arg = import_raw_type(vec![PATH_TRAITS, TRAIT_FROM_ARG]);
     let trait_from_args = import_raw_type(vec![PATH_TRAITS, TRAIT_FROM_ARGS]);
     let trait_from_app = import_raw_type(vec![PATH_TRAITS, TRAIT_FROM_APP]);
+    let ty_parse_error = import_raw_type(vec![PATH_ERRORS, TOKEN_PARSE_ERROR]);
     let fn_get_arg = if is_sub_command { format_ident!("get_sub_arg") } else { format_ident!("get_cmd_arg") };
 
     for def_arg in def_args.inner.iter() {
@@ -179,45 +212,70 @@ pub(crate) fn generate_inputs(
                     if def_arg_ty.is_multiply() {
                         really_inputs.push(quote_spanned! {span=>
                             {
-                                let mut args = &#ty_args(vec![]);
-
-                                if let Some(#ty_mixed::Multiply(_args)) = #app_ident.#fn_get_arg(#arg_name) {
-                                    args = _args;
-                                }
-
-                                if let Ok(tmp) = <#ty as #trait_from_args>::from_args(args) {
-                                    tmp
+                                // if arguments are absent, the token of error is empty
+                                let possible_values = <#ty as #trait_from_args>::possible_values();
+                                let result = if let Some(#ty_mixed::Multiply(args)) = #app_ident.#fn_get_arg(#arg_name) {
+                                    <#ty as #trait_from_args>::from_args(args).map_err(|_| match &possible_values {
+                                        // the token is the first argument which isn't accepted
+                                        Some(values) => args
+                                            .iter()
+                                            .find(|arg| !arg.to_str().map_or(false, |arg| values.contains(&arg)))
+                                            .map_or_else(|| args.to_string(), |arg| arg.to_string()),
+                                        None => args.to_string(),
+                                    })
                                 } else {
-                                    eprintln!("parse failed, can't parse input `{}` as type `{}`", args, stringify!(#ty));
-                                    std::process::exit(1);
+                                    <#ty as #trait_from_args>::from_absent().map_err(|_| String::new())
+                                };
+
+                                match (result, possible_values) {
+                                    (Ok(tmp), _) => tmp,
+                                    (Err(token), Some(values)) => return Err(#ty_parse_error::InvalidValue {
+                                        token,
+                                        possible_values: values.into_iter().map(String::from).collect(),
+                                        index: None,
+                                    }),
+                                    (Err(token), None) => return Err(#ty_parse_error::ConversionFailed {
+                                        token,
+                                        ty: String::from(stringify!(#ty)),
+                                        index: None,
+                                    }),
                                 }
                             }
                         });
                     } else {
                         really_inputs.push(quote_spanned! {span=>
                             {
-                                let mut arg = &#ty_arg(String::new());
-
-                                if let Some(#ty_mixed::Single(_arg)) = #app_ident.#fn_get_arg(#arg_name) {
-                                    arg = _arg;
-                                }
-
-                                if let Ok(tmp) = <#ty as #trait_from_arg>::from_arg(arg) {
-                                    tmp
+                                // if the argument is absent, the token of error is empty
+                                let result = if let Some(#ty_mixed::Single(arg)) = #app_ident.#fn_get_arg(#arg_name) {
+                                    <#ty as #trait_from_arg>::from_arg(arg).map_err(|_| arg.to_string())
                                 } else {
-                                    eprintln!("parse failed, can't parse input `{}` as type `{}`", arg, stringify!(#ty));
-                                    std::process::exit(1);
+                                    <#ty as #trait_from_arg>::from_absent().map_err(|_| String::new())
+                                };
+
+                                match (result, <#ty as #trait_from_arg>::possible_values()) {
+                                    (Ok(tmp), _) => tmp,
+                                    (Err(token), Some(values)) => return Err(#ty_parse_error::InvalidValue {
+                                        token,
+                                        possible_values: values.into_iter().map(String::from).collect(),
+                                        index: None,
+                                    }),
+                                    (Err(token), None) => return Err(#ty_parse_error::ConversionFailed {
+                                        token,
+                                        ty: String::from(stringify!(#ty)),
+                                        index: None,
+                                    }),
                                 }
                             }
                         })
                     }
                 } else {
+                    // the name is used by some types, e.g., `verbose: Count` is the times `--verbose` is offered
+                    let long = to_kebab_case_from_snake(&arg_name);
+
                     really_inputs.push(quote_spanned! {span=>
-                        if let Ok(tmp) = <#ty as #trait_from_app>::from_app(&#app_ident) {
-                            tmp
-                        } else {
-                            eprintln!("parse failed, can't parse type `App` as type `{}`", stringify!(#ty));
-                            std::process::exit(1);
+                        match <#ty as #trait_from_app>::from_named(&#app_ident, #long) {
+                            Ok(tmp) => tmp,
+                            Err(err) => return Err(<#ty as #trait_from_app>::parse_error(err, stringify!(#ty))),
                         }
                     });
                 }
@@ -227,4 +285,46 @@ pub(crate) fn generate_inputs(
 
     let tmp = quote! { #(#really_inputs,)* };
     tmp
-}
\ No newline at end of file
+}
+
+// set possible values of arguments according to types of inputs, e.g., `<food: noodles|beef|fish>`
+// `target` is the instance of `Command` or `SubCommand`
+pub(crate) fn generate_possible_values(
+    inputs: &Punctuated<FnArg, token::Comma>,
+    def_args: &Arguments,
+    target: &Ident,
+) -> TokenStream2 {
+    let mut args_map = HashMap::new();
+    let mut exprs = vec![];
+    let trait_from_arg = import_raw_type(vec![PATH_TRAITS, TRAIT_FROM_ARG]);
+    let trait_from_args = import_raw_type(vec![PATH_TRAITS, TRAIT_FROM_ARGS]);
+    let trait_needed = import_raw_trait(TRAIT_SET_POSSIBLE_VALUES);
+
+    for def_arg in def_args.inner.iter() {
+        args_map.insert(def_arg.name.to_string(), def_arg.ty.clone());
+    }
+
+    for input in inputs.iter() {
+        if let FnArg::Typed(pat) = input {
+            if let syn::Pat::Ident(pat_name) = &*pat.pat {
+                let arg_name = pat_name.ident.to_string();
+                let ty = &pat.ty;
+
+                match args_map.get(&arg_name) {
+                    Some(def_arg_ty) if def_arg_ty.is_multiply() => exprs.push(quote! {
+                        #target.set_possible_values(#arg_name, <#ty as #trait_from_args>::possible_values());
+                    }),
+                    Some(_) => exprs.push(quote! {
+                        #target.set_possible_values(#arg_name, <#ty as #trait_from_arg>::possible_values());
+                    }),
+                    None => {},
+                }
+            }
+        }
+    }
+
+    quote! {
+        #trait_needed;
+        #(#exprs)*
+    }
+}
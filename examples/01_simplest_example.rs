@@ -1,5 +1,3 @@
-#![feature(proc_macro_hygiene)]
-
 use commander_rust::{ option, sub_command, command, execute };
 
 #[allow(dead_code)]
@@ -1,5 +1,3 @@
-#![feature(proc_macro_hygiene)]
-
 use commander_rust::{ option, sub_command, command, execute, default_options, };
 use commander_rust::{Opts, Mixed};
 use commander_rust::traits::{ FromArg };
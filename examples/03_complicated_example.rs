@@ -1,7 +1,6 @@
 // a package manager simulation
-#![feature(proc_macro_hygiene)]
 
-use commander_rust::{sub_command, default_options, command, option, execute, Arg, Opts, Command};
+use commander_rust::{sub_command, default_options, command, option, execute, Arg, Opts, Command, FromApp};
 use commander_rust::traits::FromArg;
 use commander_rust_core::converters::GlobalOpts;
 
@@ -18,7 +17,7 @@ impl<'a> FromArg<'a> for Pkg {
     type Error = ();
 
     fn from_arg(arg: &'a Arg) -> Result<Self, Self::Error> {
-        let splits: Vec<&str> = arg.split('=').collect();
+        let splits: Vec<&str> = arg.to_str().ok_or(())?.split('=').collect();
 
         if splits.len() != 2 {
             Err(())
@@ -48,11 +47,19 @@ impl<'a> FromArg<'a> for Pkg {
     }
 }
 
+// options of `install`, `verbose` is a global option
+#[derive(FromApp)]
+struct InstallOpts {
+    force: bool,
+    global: bool,
+    verbose: bool,
+}
+
 #[default_options]
 #[option(-f, --force, "force to install even if this package has already installed")]
 #[option(-g, --global, "install as a global package")]
-#[sub_command(install <pkg>, "install a package")]
-fn install_fn(pkg: Result<Pkg, ()>, opts: Opts, global_opts: GlobalOpts) {
+#[sub_command(install|i <pkg>, "install a package")]
+fn install_fn(pkg: Result<Pkg, ()>, opts: InstallOpts) {
     if let Ok(pkg) = pkg {
         let node_pkg = format!("{}@{}.{}.{}", pkg.name, pkg.version.0, pkg.version.1, pkg.version.2);
         println!("try to install {}", node_pkg);
@@ -62,15 +69,15 @@ fn install_fn(pkg: Result<Pkg, ()>, opts: Opts, global_opts: GlobalOpts) {
         cmd.arg("install");
         cmd.arg(&node_pkg);
 
-        if global_opts.contains_key("verbose") {
+        if opts.verbose {
             cmd.arg("--verbose");
         }
 
-        if opts.contains_key("force") {
+        if opts.force {
             cmd.arg("--force");
         }
 
-        if opts.contains_key("global") {
+        if opts.global {
             cmd.arg("--global");
         }
 
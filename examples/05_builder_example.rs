@@ -0,0 +1,35 @@
+use commander_rust::{ Program, Application, Mixed };
+
+// commands could be loaded from plugins, e.g., here, from a list
+const PLUGINS: [(&str, &str); 2] = [("lint [..files]", "lint files"), ("fmt [..files]", "format files")];
+
+fn files(app: &Application) -> Vec<String> {
+    match app.get_sub_arg("files") {
+        Some(Mixed::Multiply(files)) => files.iter().map(|file| file.to_string()).collect(),
+        Some(Mixed::Single(file)) => vec![file.to_string()],
+        _ => vec![],
+    }
+}
+
+fn main() {
+    let mut program = Program::new("tools")
+        .version("0.0.1")
+        .description("tools loaded at runtime")
+        .option("--[no-]color", "colorize the output");
+
+    for (spec, desc) in PLUGINS.iter() {
+        let name = spec.split(' ').next().unwrap_or_default().to_string();
+
+        program = program
+            .command(spec)
+            .description(desc)
+            .option("-q, --quiet", "don't print anything")
+            .action(move |app: &Application| {
+                if !app.contains_opt("quiet") {
+                    println!("{} {:?}, color: {:?}", name, files(app), app.get_flag("color"));
+                }
+            });
+    }
+
+    program.run();
+}
@@ -1,4 +1,3 @@
-#![feature(proc_macro_hygiene)]
 #![allow(dead_code)]
 #![allow(unused_variables)]
 use commander_rust::{ command, sub_command, option, default_options, execute };
@@ -17,7 +16,7 @@ impl<'a> FromArg<'a> for Address {
     type Error = ();
 
     fn from_arg(arg: &'a Arg) -> Result<Self, Self::Error> {
-        let parts: Vec<&str> = arg.split_terminator(':').collect();
+        let parts: Vec<&str> = arg.to_str().ok_or(())?.split_terminator(':').collect();
 
         if parts.len() != 2 { Err(()) } else {
             let ipv4 = if let Ok(ipv4) = Ipv4Addr::from_str(parts[0]) { ipv4 } else {
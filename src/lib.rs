@@ -1,10 +1,67 @@
 pub use commander_rust_macro::*;
-pub use commander_rust_core::{ ArgumentType, Argument, Options, SubCommand, Command };
-pub use commander_rust_core::converters::{ Application, Opts, GlobalOpts, Arg, Args, Mixed };
+pub use commander_rust_core::{ ArgumentType, Argument, Options, RepeatPolicy, OptionGroup, GroupKind, SubCommand, Command, NegativeNumbers };
+pub use commander_rust_core::converters::{ Application, Opts, GlobalOpts, Arg, Args, Mixed, Count };
+pub use commander_rust_core::builder::Program;
 pub mod traits {
     pub use commander_rust_core::traits::*;
-    pub use commander_rust_core::converters::{ FromArg, FromArgs, FromApp };
+    pub use commander_rust_core::converters::{ FromArg, FromArgs, FromApp, FieldFromApp, FieldFromArg };
 }
 pub mod parser {
     pub use commander_rust_core::parser::*;
 }
+pub mod errors {
+    pub use commander_rust_core::errors::*;
+}
+pub mod completions {
+    pub use commander_rust_core::completions::*;
+}
+pub mod man {
+    pub use commander_rust_core::man::*;
+}
+pub mod spec {
+    pub use commander_rust_core::spec::*;
+}
+pub mod config {
+    pub use commander_rust_core::config::*;
+}
+pub mod response {
+    pub use commander_rust_core::response::*;
+}
+pub mod register {
+    pub use commander_rust_core::register::*;
+}
+
+/// Errors of macros reported when compiling, e.g., names of constraints which are neither options nor arguments.
+///
+/// ```compile_fail
+/// use commander_rust::{ option, command, execute };
+///
+/// #[option(-q, --quiet, "print nothing", conflicts_with = "verbos")]
+/// #[option(--verbose, "print more")]
+/// #[command(tool [file], "a tool")]
+/// fn tool(file: Option<String>) {}
+///
+/// fn main() {
+///     execute!(tool, []);
+/// }
+/// ```
+///
+/// Options of sub-commands could refer to options of the command, they are checked by `execute!`.
+///
+/// ```compile_fail,E0080
+/// use commander_rust::{ option, sub_command, command, execute };
+///
+/// #[option(-q, --quiet, "print nothing", requires = "verbos")]
+/// #[sub_command(run <file>, "run a file")]
+/// fn run(file: String) {}
+///
+/// #[option(--verbose, "print more")]
+/// #[command(tool, "a tool")]
+/// fn tool() {}
+///
+/// fn main() {
+///     execute!(tool, [run]);
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct CompileErrors;
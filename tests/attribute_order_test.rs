@@ -0,0 +1,47 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, sub_command, command };
+use commander_rust::traits::GetOpts;
+use common::{ record, ok };
+
+// options could be above or below the sub-command, they are kept in order
+#[option(-f, --force, "force to remove")]
+#[sub_command(rm <file>, "remove a file")]
+#[option(-r, --recursive, "remove directories")]
+#[default_options]
+fn rm(file: String, app: &commander_rust::Application) {
+    record(format!("rm {} {} {}", file, app.contains_opt("force"), app.contains_opt("recursive")));
+}
+
+#[sub_command(ls [dir], "list a directory")]
+#[option(-a, --all, "list hidden files")]
+fn ls(dir: Option<String>, app: &commander_rust::Application) {
+    record(format!("ls {} {}", dir.unwrap_or_default(), app.contains_opt("all")));
+}
+
+#[command(files, "file utils")]
+#[completions]
+#[default_options]
+fn files() {}
+
+// more than one command could be defined, each `execute!` runs one of them
+#[option(-n, --lines <n>, "number of lines")]
+#[command(head <file>, "print the first lines")]
+fn head(file: String) {
+    record(format!("head {}", file));
+}
+
+#[test]
+fn attributes_in_any_order() {
+    let sub_command = _commander_rust_prefix_rm_commander_rust_suffix_();
+    let longs: Vec<&str> = sub_command.get_opts().iter().map(|opt| opt.long.as_str()).collect();
+
+    assert_eq!(longs, vec!["force", "recursive", "version", "help"]);
+    assert_eq!(run!(files, [rm, ls], vec!["files", "rm", "a.txt", "-r"]), ok("rm a.txt false true"));
+    assert_eq!(run!(files, [rm, ls], vec!["files", "ls", "-a"]), ok("ls  true"));
+    // `#[completions]` below the command works too
+    assert_eq!(run!(files, [rm, ls], vec!["files", "completions", "bash"]), (0, vec![]));
+    assert_eq!(run!(head, [], vec!["head", "a.txt", "-n", "5"]), ok("head a.txt"));
+    assert_eq!(run!(head, [], vec!["head", "a.txt", "-f"]), (1, vec![]));
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use commander_rust::{ Program, Application, Mixed };
+use common::{ record, records };
+
+fn single(arg: Option<&Mixed>) -> String {
+    match arg {
+        Some(Mixed::Single(s)) => s.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn npms() -> Program {
+    Program::new("npms")
+        .version("1.2.3")
+        .description("package manager")
+        .option("-r, --registry <url>", "registry to use")
+        .arguments("[script]")
+        .action(|app: &Application| record(format!("npms {}", single(app.get_cmd_arg("script")))))
+        .command("install|i <pkg>")
+        .description("install a package")
+        .option("-g, --global", "install globally")
+        .action(|app: &Application| {
+            let registry = app.get_opt_args("registry").map(|args| args[0].to_string()).unwrap_or_default();
+
+            record(format!("install {} {} {}", single(app.get_sub_arg("pkg")), app.contains_opt("global"), registry));
+        })
+        .command("remote")
+        .alias("rm")
+        .command("remote add <name> <url>")
+        .action(|app: &Application| -> Result<(), String> {
+            if single(app.get_sub_arg("name")) == "origin" {
+                Err(String::from("remote `origin` exists"))
+            } else {
+                record(format!("{} {}", app.sub_path(), single(app.get_sub_arg("url"))));
+                Ok(())
+            }
+        })
+}
+
+#[test]
+fn builder() {
+    let program = npms();
+
+    assert_eq!(program.run_from(vec!["npms", "test"]), 0);
+    assert_eq!(program.run_from(vec!["npms", "i", "lodash", "-g", "-r", "cdn"]), 0);
+    assert_eq!(program.run_from(vec!["npms", "install", "react"]), 0);
+    assert_eq!(program.run_from(vec!["npms", "rm", "add", "up", "url"]), 0);
+    // sub-commands without actions
+    assert_eq!(program.run_from(vec!["npms", "remote"]), 0);
+    assert_eq!(
+        records(),
+        vec!["npms test", "install lodash true cdn", "install react false ", "remote add url"],
+    );
+
+    // errors of actions and parse errors
+    assert_eq!(program.run_from(vec!["npms", "remote", "add", "origin", "url"]), 1);
+    assert_eq!(program.run_from(vec!["npms", "install"]), 1);
+    assert_eq!(program.run_from(vec!["npms", "install", "a", "--unknown"]), 1);
+    assert_eq!(program.run_from(vec!["npms", "--help"]), 0);
+    assert_eq!(program.run_from(vec!["npms", "-v"]), 0);
+    assert!(records().is_empty());
+
+    let help = format!("{}", program.as_command());
+
+    assert!(help.contains("    install, i    install a package\n"));
+    assert!(help.contains("    -r, --registry <url>    registry to use\n"));
+    assert_eq!(program.as_command().version, "1.2.3");
+}
+
+#[test]
+#[should_panic(expected = "short name of option should be one character")]
+fn builder_invalid_option() {
+    Program::new("npms").option("-bad, --option", "");
+}
+
+#[test]
+#[should_panic(expected = "invalid option `--global`, it's duplicated")]
+fn builder_duplicated_option() {
+    Program::new("npms")
+        .command("install <pkg>")
+        .option("-g, --global", "")
+        .option("--global", "install globally");
+}
+
+#[test]
+#[should_panic(expected = "invalid option `--host`, it's duplicated")]
+fn builder_duplicated_short_option() {
+    Program::new("npms").option("-h, --host <host>", "");
+}
+
+#[test]
+#[should_panic(expected = "unknown name")]
+fn builder_unknown_constraint_name() {
+    Program::new("npms")
+        .option("-q, --quiet, conflicts_with = \"verbos\"", "")
+        .option("--verbose", "")
+        .run_from(vec!["npms"]);
+}
@@ -0,0 +1,29 @@
+// helpers shared by integration tests, not all of them are used by every test file
+#![allow(dead_code)]
+
+use std::sync::Mutex;
+
+static RECORDS: Mutex<Vec<String>> = Mutex::new(vec![]);
+
+/// Records what a handler is called with.
+pub fn record<T: ToString>(record: T) {
+    RECORDS.lock().unwrap().push(record.to_string());
+}
+
+/// Takes records of handlers called since last time.
+pub fn records() -> Vec<String> {
+    RECORDS.lock().unwrap().drain(..).collect()
+}
+
+/// The result of a run which succeeds and calls only one handler.
+pub fn ok(record: &str) -> (i32, Vec<String>) {
+    (0, vec![record.to_string()])
+}
+
+/// Executes the command with argv offered, returns the exit code and records of handlers called.
+#[allow(unused_macros)]
+macro_rules! run {
+    ($cmd: ident, [$($sub_fn: ident),*], $argv: expr) => {
+        (commander_rust::execute!($cmd, [$($sub_fn),*], $argv), crate::common::records())
+    };
+}
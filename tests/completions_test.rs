@@ -0,0 +1,21 @@
+use commander_rust::{ sub_command, command, execute, default_options, completions };
+
+#[default_options]
+#[sub_command(install <pkg>, "install a package")]
+fn install(pkg: String) {
+    assert!(!pkg.is_empty());
+}
+
+#[completions]
+#[default_options]
+#[command(pkg, "package manager")]
+fn pkg() {}
+
+#[test]
+fn built_in_sub_command() {
+    assert_eq!(execute!(pkg, [install], vec!["pkg", "completions", "bash"]), 0);
+    assert_eq!(execute!(pkg, [install], vec!["pkg", "completions", "fish"]), 0);
+    assert_eq!(execute!(pkg, [install], vec!["pkg", "completions", "--help"]), 0);
+    assert_eq!(execute!(pkg, [install], vec!["pkg", "completions", "tcsh"]), 1);
+    assert_eq!(execute!(pkg, [install], vec!["pkg", "completions"]), 1);
+}
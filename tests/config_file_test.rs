@@ -0,0 +1,147 @@
+#![cfg(feature = "config")]
+
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, command, sub_command, Application, FromApp };
+use commander_rust::errors::ParseError;
+use commander_rust::parser::Source;
+use std::path::PathBuf;
+use common::{ record, ok };
+
+fn value(app: &Application, long: &str) -> String {
+    app.get_opt_args(long).map_or(String::from("-"), |args| args.to_string())
+}
+
+fn source(app: &Application, long: &str) -> String {
+    match app.get_opt_source(long) {
+        Some(Source::Cli) => String::from("cli"),
+        Some(Source::Env(_)) => String::from("env"),
+        Some(Source::Config { key, .. }) => format!("config:{}", key),
+        Some(Source::Default) => String::from("default"),
+        None => String::from("-"),
+    }
+}
+
+#[option(-g, --global, "install globally")]
+#[option(--tags <..tags>, "tags of the package")]
+#[sub_command(install <pkg>, "install a package")]
+fn install(pkg: String, app: &Application) {
+    record(format!(
+        "{} {} {} {} {} {} {}",
+        pkg,
+        value(app, "registry"), source(app, "registry"),
+        app.contains_opt("global"), source(app, "global"),
+        value(app, "retries"), source(app, "retries"),
+    ));
+}
+
+#[derive(FromApp)]
+struct PublishOpts {
+    retries: u8,
+}
+
+#[sub_command(publish, "publish the package")]
+fn publish(opts: Result<PublishOpts, ParseError>) {
+    record(match opts {
+        Ok(opts) => opts.retries.to_string(),
+        Err(err) => format!("{:?}", err),
+    });
+}
+
+#[option(-c, --config <path>, "config file", env = "COMMANDER_RUST_TEST_CONFIG")]
+#[option(-r, --registry <url>, "registry to use", env = "COMMANDER_RUST_TEST_CONFIG_REGISTRY")]
+#[option(--retries <n> = "3", "times to retry", env = "COMMANDER_RUST_TEST_CONFIG_RETRIES")]
+#[command(npms, "package manager", config_option = "config", config_file = "commander-rust-test-missing.toml")]
+fn npms() {}
+
+fn write(name: &str, content: &str) -> String {
+    let path: PathBuf = std::env::temp_dir().join(format!("commander-rust-{}-{}", std::process::id(), name));
+
+    std::fs::write(&path, content).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+#[test]
+fn config_layering() {
+    let run = |argv: Vec<&str>| run!(npms, [install, publish], argv);
+
+    // environment variables are modified in only one test to avoid races
+    std::env::remove_var("COMMANDER_RUST_TEST_CONFIG");
+    std::env::remove_var("COMMANDER_RUST_TEST_CONFIG_REGISTRY");
+    std::env::remove_var("COMMANDER_RUST_TEST_CONFIG_RETRIES");
+
+    let toml = write("npms.toml", r#"
+        registry = "config.com"
+        retries = 5
+
+        [install]
+        global = true
+        tags = ["a", "b"]
+    "#);
+    let json = write("npms.json", r#"{ "registry": "json.com", "install": { "global": false } }"#);
+
+    // the default location doesn't exist
+    assert_eq!(run(vec!["npms", "install", "pkg"]), ok("pkg - - false - 3 default"));
+    assert_eq!(
+        run(vec!["npms", "install", "pkg", "-c", &toml]),
+        ok("pkg config.com config:registry true config:install.global 5 config:retries"),
+    );
+    assert_eq!(
+        run(vec!["npms", "install", "pkg", "--config", &json]),
+        ok("pkg json.com config:registry false - 3 default"),
+    );
+    // command line > config file
+    assert_eq!(
+        run(vec!["npms", "install", "pkg", "-g", "-c", &toml, "-r", "cli.com", "--retries", "1"]),
+        ok("pkg cli.com cli true cli 1 cli"),
+    );
+
+    // command line > environment variables > config file
+    std::env::set_var("COMMANDER_RUST_TEST_CONFIG_REGISTRY", "env.com");
+    std::env::set_var("COMMANDER_RUST_TEST_CONFIG", &toml);
+    assert_eq!(
+        run(vec!["npms", "install", "pkg"]),
+        ok("pkg env.com env true config:install.global 5 config:retries"),
+    );
+    assert_eq!(
+        run(vec!["npms", "install", "pkg", "-r", "cli.com"]),
+        ok("pkg cli.com cli true config:install.global 5 config:retries"),
+    );
+    std::env::remove_var("COMMANDER_RUST_TEST_CONFIG");
+    std::env::remove_var("COMMANDER_RUST_TEST_CONFIG_REGISTRY");
+
+    // values which fail to be converted report where they come from
+    let bad_retries = write("bad_retries.toml", "retries = \"many\"\n");
+
+    assert_eq!(
+        run(vec!["npms", "publish", "-c", &bad_retries]),
+        ok(&format!(
+            "InvalidConfig {{ token: {:?}, key: \"retries\", message: \"value of `retries`, `many` can't be parsed as type `u8`\", index: None }}",
+            bad_retries,
+        )),
+    );
+    std::env::set_var("COMMANDER_RUST_TEST_CONFIG_RETRIES", "many");
+    assert_eq!(
+        run(vec!["npms", "publish", "-c", &toml]),
+        ok(
+            "InvalidEnv { token: \"COMMANDER_RUST_TEST_CONFIG_RETRIES\", message: \"`many` can't be parsed as type `u8`\", index: None }",
+        ),
+    );
+    assert_eq!(run(vec!["npms", "publish", "--retries", "2"]), ok("2"));
+    std::env::remove_var("COMMANDER_RUST_TEST_CONFIG_RETRIES");
+
+    // unknown keys, invalid values, malformed files and missing files offered
+    let unknown = write("unknown.toml", "[install]\nforce = true\n");
+    let not_flag = write("not_flag.toml", "[install]\nglobal = \"yes\"\n");
+    let malformed = write("malformed.toml", "registry = \n");
+
+    assert_eq!(run(vec!["npms", "install", "pkg", "-c", &unknown]), (1, vec![]));
+    assert_eq!(run(vec!["npms", "install", "pkg", "-c", &not_flag]), (1, vec![]));
+    assert_eq!(run(vec!["npms", "install", "pkg", "-c", &malformed]), (1, vec![]));
+    assert_eq!(run(vec!["npms", "install", "pkg", "-c", "commander-rust-test-missing.toml"]), (1, vec![]));
+
+    for path in [toml, json, bad_retries, unknown, not_flag, malformed].iter() {
+        std::fs::remove_file(path).unwrap();
+    }
+}
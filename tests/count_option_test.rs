@@ -0,0 +1,41 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, sub_command, command, FromApp, Count };
+use common::{ record, ok };
+
+// inputs and fields are converted by traits, so aliases of `Count` are counted too
+type Times = Count;
+
+#[derive(FromApp)]
+struct LogOpts {
+    quiet: Count,
+    verbose: Count,
+    dry_run: Times,
+}
+
+#[option(-q, --quiet, "less output")]
+#[sub_command(log [path], "show logs")]
+fn log(path: Option<String>, opts: LogOpts) {
+    record(format!("{:?} {} {} {}", path, *opts.quiet, *opts.verbose, *opts.dry_run));
+}
+
+#[option(-V, --verbose, "more output, could be repeated")]
+#[option(--dry-run, "print actions only")]
+#[command(vcs, "version control")]
+fn vcs(verbose: Count, dry_run: Option<Times>) {
+    record(format!("{} {}", verbose.0, dry_run.unwrap().0));
+}
+
+#[test]
+fn count_options() {
+    assert_eq!(run!(vcs, [log], vec!["vcs"]), ok("0 0"));
+    assert_eq!(run!(vcs, [log], vec!["vcs", "-V"]), ok("1 0"));
+    assert_eq!(run!(vcs, [log], vec!["vcs", "-VVV", "--dry-run"]), ok("3 1"));
+    assert_eq!(run!(vcs, [log], vec!["vcs", "-VV", "--verbose", "--dry-run", "--dry-run"]), ok("3 2"));
+    // local options and global options are both counted
+    assert_eq!(run!(vcs, [log], vec!["vcs", "log", "-qq", "-V"]), ok("None 2 1 0"));
+    assert_eq!(run!(vcs, [log], vec!["vcs", "log", "a.log", "-qVqVq"]), ok(r#"Some("a.log") 3 2 0"#));
+    // fields of aliased types are counted too
+    assert_eq!(run!(vcs, [log], vec!["vcs", "log", "--dry-run", "--dry-run"]), ok("None 0 0 2"));
+}
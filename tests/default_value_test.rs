@@ -0,0 +1,30 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, sub_command, command, Opts, Mixed };
+use common::{ record, ok };
+
+#[option(-p, --port <port> = "8080", "port to listen")]
+#[sub_command(serve [host = "localhost"] [root], "serve files")]
+fn serve(host: String, root: Option<String>, opts: Opts) {
+    let port = match opts.get("port").and_then(|port| port.get("port")) {
+        Some(Mixed::Single(port)) => port.to_string(),
+        _ => String::new(),
+    };
+
+    record(format!("{}:{} {:?}", host, port, root));
+}
+
+#[command(server [level] [..files], "file server")]
+fn server(level: Option<u8>, files: Option<Vec<String>>) {
+    record(format!("{:?} {:?}", level, files));
+}
+
+#[test]
+fn default_values() {
+    assert_eq!(run!(server, [serve], vec!["server", "serve"]), ok("localhost:8080 None"));
+    assert_eq!(run!(server, [serve], vec!["server", "serve", "0.0.0.0", "/srv", "-p", "80"]), ok("0.0.0.0:80 Some(\"/srv\")"));
+    // absent arguments are `None` instead of converting from an empty string
+    assert_eq!(run!(server, [serve], vec!["server"]), ok("None None"));
+    assert_eq!(run!(server, [serve], vec!["server", "1", "a", "b"]), ok("Some(1) Some([\"a\", \"b\"])"));
+}
@@ -0,0 +1,88 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, sub_command, command, FromApp, FromArg };
+use commander_rust::errors::ParseError;
+use common::{ record, ok };
+
+#[derive(FromArg, Debug)]
+enum Registry {
+    Npm,
+    Yarn,
+}
+
+#[derive(FromApp)]
+struct InstallOpts {
+    force: bool,
+    dry_run: bool,
+    // global option
+    verbose: bool,
+    registry: Option<Registry>,
+    retries: u8,
+    #[opt(name = "exclude")]
+    excluded: Vec<String>,
+    size: Option<Vec<u32>>,
+}
+
+#[option(-f, --force, "force to install")]
+#[option(--dry-run, "print actions only")]
+#[option(--registry <registry>, "registry to use")]
+#[option(--retries <times> = "3", "times to retry")]
+#[option(--exclude <..pkgs>, "packages excluded")]
+#[option(--size <width> <height>, "size of window")]
+#[sub_command(install <pkg>, "install a package")]
+fn install(pkg: String, opts: Result<InstallOpts, ParseError>) {
+    record(match opts {
+        Ok(opts) => format!(
+            "{} {} {} {} {:?} {} {:?} {:?}",
+            pkg, opts.force, opts.dry_run, opts.verbose, opts.registry, opts.retries, opts.excluded, opts.size,
+        ),
+        Err(e) => format!("{:?}", e),
+    });
+}
+
+#[derive(FromApp)]
+struct PublishOpts {
+    registry: Registry,
+}
+
+#[option(--registry <registry>, "registry to publish to")]
+#[sub_command(publish, "publish the package")]
+fn publish(opts: Result<PublishOpts, ParseError>) {
+    record(match opts {
+        Ok(opts) => format!("{:?}", opts.registry),
+        Err(e) => format!("{:?}", e),
+    });
+}
+
+#[option(--verbose, "display verbose information")]
+#[command(pkg, "package manager")]
+fn pkg() {}
+
+#[test]
+fn derive_from_app() {
+    assert_eq!(
+        run!(pkg, [install, publish], vec!["pkg", "install", "react"]),
+        ok("react false false false None 3 [] None"),
+    );
+    assert_eq!(
+        run!(pkg, [install, publish], vec![
+            "pkg", "install", "react", "-f", "--dry-run", "--registry", "yarn", "--retries", "5",
+            "--exclude", "a", "b", "--size", "800", "600", "--verbose",
+        ]),
+        ok("react true true true Some(Yarn) 5 [\"a\", \"b\"] Some([800, 600])"),
+    );
+    assert_eq!(
+        run!(pkg, [install, publish], vec!["pkg", "install", "react", "--retries", "many"]),
+        ok(r#"ConversionFailed { token: "many", ty: "u8", index: None }"#),
+    );
+    assert_eq!(
+        run!(pkg, [install, publish], vec!["pkg", "publish", "--registry", "pip"]),
+        ok(r#"InvalidValue { token: "pip", possible_values: ["npm", "yarn"], index: None }"#),
+    );
+    // the option is the token if its argument is absent
+    assert_eq!(
+        run!(pkg, [install, publish], vec!["pkg", "publish"]),
+        ok(r#"InvalidValue { token: "--registry", possible_values: ["npm", "yarn"], index: None }"#),
+    );
+}
@@ -0,0 +1,64 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ sub_command, command, Arg, FromArg };
+use commander_rust::traits::{ FromArg, GetArgs };
+use common::{ record, ok };
+
+#[derive(FromArg, Debug, Eq, PartialEq)]
+enum Food {
+    Noodles,
+    Beef,
+    FriedFish,
+    #[arg(name = "ice-cream")]
+    Dessert,
+}
+
+#[derive(FromArg, Debug)]
+enum Drink {
+    Water,
+    Tea,
+}
+
+#[sub_command(drink [..drinks], "drink something")]
+fn drink(drinks: Vec<Drink>) {
+    record(format!("{:?}", drinks));
+}
+
+#[command(eat <food>, "eat food")]
+fn eat(food: Food) {
+    record(format!("{:?}", food));
+}
+
+fn arg(s: &str) -> Arg {
+    Arg::from(s)
+}
+
+#[test]
+fn derive_from_arg() {
+    assert_eq!(Food::from_arg(&arg("noodles")), Ok(Food::Noodles));
+    assert_eq!(Food::from_arg(&arg("fried-fish")), Ok(Food::FriedFish));
+    assert_eq!(Food::from_arg(&arg("ice-cream")), Ok(Food::Dessert));
+    assert_eq!(
+        Food::from_arg(&arg("dessert")),
+        Err(String::from("invalid value `dessert`, possible values are `noodles, beef, fried-fish, ice-cream`")),
+    );
+    assert_eq!(Food::possible_values(), Some(vec!["noodles", "beef", "fried-fish", "ice-cream"]));
+    assert_eq!(<Option<Vec<Drink>> as commander_rust::traits::FromArgs>::possible_values(), Some(vec!["water", "tea"]));
+}
+
+#[test]
+fn possible_values_in_help_and_errors() {
+    let cmd = _commander_rust_prefix_eat_commander_rust_suffix_();
+
+    assert_eq!(cmd.get_args()[0].to_string(), "<food: noodles|beef|fried-fish|ice-cream>");
+
+    assert_eq!(run!(eat, [drink], vec!["eat", "beef"]), ok("Beef"));
+    assert_eq!(run!(eat, [drink], vec!["eat", "bread"]), (1, vec![]));
+    assert_eq!(
+        run!(eat, [drink], vec!["eat", "beef", "drink", "tea", "water"]),
+        (0, vec![String::from("Beef"), String::from("[Tea, Water]")]),
+    );
+    // the command is called before the sub-command fails
+    assert_eq!(run!(eat, [drink], vec!["eat", "beef", "drink", "tea", "wine"]), (1, vec![String::from("Beef")]));
+}
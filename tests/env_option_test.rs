@@ -0,0 +1,61 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, command, Application, GlobalOpts, Mixed };
+use common::{ record, ok };
+
+// names of arguments are the same as names of options
+fn get(opts: &GlobalOpts, opt: &str) -> String {
+    match opts.get(opt).and_then(|args| args.get(opt)) {
+        Some(Mixed::Single(arg)) => arg.to_string(),
+        Some(Mixed::Multiply(args)) => args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>().join(","),
+        None => String::from("-"),
+    }
+}
+
+#[option(-t, --token <token>, "api token", env = "COMMANDER_RUST_TEST_TOKEN")]
+#[option(--hosts <..hosts>, "hosts to deploy", env = "COMMANDER_RUST_TEST_HOSTS")]
+#[option(--size <width> <height>, "size of window", env = "COMMANDER_RUST_TEST_SIZE")]
+#[option(--dry-run, env = "COMMANDER_RUST_TEST_DRY_RUN")]
+#[command(deploy, "deploy tool")]
+fn deploy(opts: GlobalOpts, app: &Application) {
+    record(format!(
+        "{} {} {} {:?}",
+        get(&opts, "token"),
+        get(&opts, "hosts"),
+        opts.contains_key("dry-run"),
+        app.get_opt_source("dry-run"),
+    ));
+}
+
+#[test]
+fn env_fallback() {
+    // environment variables are modified in only one test to avoid races
+    std::env::remove_var("COMMANDER_RUST_TEST_TOKEN");
+    std::env::remove_var("COMMANDER_RUST_TEST_HOSTS");
+    std::env::remove_var("COMMANDER_RUST_TEST_DRY_RUN");
+    std::env::remove_var("COMMANDER_RUST_TEST_SIZE");
+    assert_eq!(run!(deploy, [], vec!["deploy"]), ok("- - false None"));
+
+    std::env::set_var("COMMANDER_RUST_TEST_TOKEN", "secret token");
+    std::env::set_var("COMMANDER_RUST_TEST_HOSTS", "a.com  b.com");
+    std::env::set_var("COMMANDER_RUST_TEST_DRY_RUN", "1");
+    // single argument isn't split, multiply arguments are split by whitespaces
+    assert_eq!(run!(deploy, [], vec!["deploy"]), ok(r#"secret token a.com,b.com true Some(Env("COMMANDER_RUST_TEST_DRY_RUN"))"#));
+    // options offered take precedence over environment variables
+    assert_eq!(
+        run!(deploy, [], vec!["deploy", "-t", "cli", "--hosts", "c.com"]),
+        ok(r#"cli c.com true Some(Env("COMMANDER_RUST_TEST_DRY_RUN"))"#),
+    );
+
+    std::env::set_var("COMMANDER_RUST_TEST_DRY_RUN", "false");
+    std::env::set_var("COMMANDER_RUST_TEST_TOKEN", "");
+    // empty value is regarded as unset, and the flag which is off isn't sourced from the environment variable
+    assert_eq!(run!(deploy, [], vec!["deploy"]), ok("- a.com,b.com false None"));
+    std::env::set_var("COMMANDER_RUST_TEST_DRY_RUN", "0");
+    assert_eq!(run!(deploy, [], vec!["deploy"]), ok("- a.com,b.com false None"));
+
+    std::env::set_var("COMMANDER_RUST_TEST_SIZE", "800");
+    // arguments from environment variables are validated too
+    assert_eq!(run!(deploy, [], vec!["deploy"]), (1, vec![]));
+}
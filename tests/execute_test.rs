@@ -0,0 +1,47 @@
+use commander_rust::{ option, sub_command, command, execute, default_options };
+use std::sync::atomic::{ AtomicUsize, AtomicU32, Ordering };
+
+static CMD_CALLED: AtomicUsize = AtomicUsize::new(0);
+static ADD_CALLED: AtomicUsize = AtomicUsize::new(0);
+static SUM: AtomicU32 = AtomicU32::new(0);
+
+#[default_options]
+#[sub_command(add <a> <b>, "add two numbers")]
+fn add(a: u32, b: u32) {
+    ADD_CALLED.fetch_add(1, Ordering::SeqCst);
+    SUM.store(a + b, Ordering::SeqCst);
+}
+
+#[default_options]
+#[option(--verbose, "display verbose information")]
+#[command(calc, "calculator")]
+fn calc() {
+    CMD_CALLED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn execute_from_argv() {
+    let code = execute!(calc, [add], vec!["calc", "add", "1", "2"]);
+    assert_eq!(code, 0);
+    assert_eq!(ADD_CALLED.load(Ordering::SeqCst), 1);
+    assert_eq!(SUM.load(Ordering::SeqCst), 3);
+
+    // terminators don't call any function
+    execute!(calc, [add], vec!["calc", "--help"]);
+    execute!(calc, [add], vec!["calc", "add", "--version"]);
+    assert_eq!(CMD_CALLED.load(Ordering::SeqCst), 0);
+    assert_eq!(ADD_CALLED.load(Ordering::SeqCst), 1);
+
+    let argv = vec!["calc".to_string(), "--verbose".to_string()];
+
+    execute!(calc, [add], argv);
+    assert_eq!(CMD_CALLED.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn execute_with_errors() {
+    // parse errors and conversion errors don't exit, but return exit code
+    assert_eq!(execute!(calc, [add], vec!["calc", "add", "1"]), 1);
+    assert_eq!(execute!(calc, [add], vec!["calc", "add", "1", "two"]), 1);
+    assert_eq!(execute!(calc, [add], vec!["calc", "--unknown"]), 1);
+}
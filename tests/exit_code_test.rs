@@ -0,0 +1,73 @@
+use commander_rust::{ sub_command, command, execute };
+use commander_rust::traits::IntoExitCode;
+use std::sync::atomic::{ AtomicUsize, Ordering };
+
+static SYNC_CALLED: AtomicUsize = AtomicUsize::new(0);
+
+enum Status {
+    Clean,
+    Dirty(u32),
+}
+
+impl IntoExitCode for Status {
+    fn into_exit_code(self) -> i32 {
+        match self {
+            Status::Clean => 0,
+            // the exit code carries the number of dirty files
+            Status::Dirty(count) => 10 + count as i32,
+        }
+    }
+}
+
+#[sub_command(check [dirty], "check the working tree")]
+fn check(dirty: Option<u32>) -> Status {
+    match dirty {
+        Some(count) => Status::Dirty(count),
+        None => Status::Clean,
+    }
+}
+
+#[sub_command(push <remote>, "push to the remote")]
+fn push(remote: String) -> Result<(), String> {
+    if remote == "origin" {
+        Ok(())
+    } else {
+        Err(format!("unknown remote `{}`", remote))
+    }
+}
+
+#[sub_command(sync, "sync with the remote")]
+fn sync() {
+    SYNC_CALLED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[command(vcs [code], "version control")]
+fn vcs(code: Option<i32>) -> i32 {
+    code.unwrap_or(0)
+}
+
+#[test]
+fn exit_codes() {
+    assert_eq!(execute!(vcs, [check, push, sync], vec!["vcs", "check"]), 0);
+    assert_eq!(execute!(vcs, [check, push, sync], vec!["vcs", "check", "2"]), 12);
+    assert_eq!(execute!(vcs, [check, push, sync], vec!["vcs", "push", "origin"]), 0);
+    // errors returned are printed, and the exit code is 1
+    assert_eq!(execute!(vcs, [check, push, sync], vec!["vcs", "push", "upstream"]), 1);
+    assert_eq!(execute!(vcs, [check, push, sync], vec!["vcs"]), 0);
+    assert_eq!(execute!(vcs, [check, push, sync], vec!["vcs", "42"]), 42);
+}
+
+#[test]
+fn sub_command_is_not_called_if_command_failed() {
+    assert_eq!(execute!(vcs, [check, push, sync], vec!["vcs", "0", "sync"]), 0);
+    assert_eq!(SYNC_CALLED.load(Ordering::SeqCst), 1);
+    assert_eq!(execute!(vcs, [check, push, sync], vec!["vcs", "2", "sync"]), 2);
+    assert_eq!(SYNC_CALLED.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn into_exit_code() {
+    assert_eq!(().into_exit_code(), 0);
+    assert_eq!(Ok::<i32, String>(5).into_exit_code(), 5);
+    assert_eq!(Err::<(), &str>("failed").into_exit_code(), 1);
+}
@@ -0,0 +1,42 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, command, FromApp, Application };
+use common::{ record, ok };
+
+#[derive(FromApp)]
+struct PrintOpts {
+    color: bool,
+    pager: bool,
+}
+
+#[option(--[no-]color, "colorize the output")]
+#[option(-p, --[no-]pager, "page the output", env = "PRINT_PAGER")]
+#[command(print [file], "print files")]
+fn print(file: Option<String>, opts: PrintOpts, app: &Application) {
+    record(format!(
+        "{:?} {} {} {:?} {:?}",
+        file, opts.color, opts.pager, app.get_flag("color"), app.get_flag("pager"),
+    ));
+}
+
+#[test]
+fn negatable_options() {
+    assert_eq!(run!(print, [], vec!["print"]), ok("None false false None None"));
+    assert_eq!(run!(print, [], vec!["print", "a.txt", "--color"]), ok(r#"Some("a.txt") true false Some(true) None"#));
+    assert_eq!(run!(print, [], vec!["print", "a.txt", "--no-color"]), ok(r#"Some("a.txt") false false Some(false) None"#));
+    // the last one wins
+    assert_eq!(run!(print, [], vec!["print", "a.txt", "--no-color", "--color"]), ok(r#"Some("a.txt") true false Some(true) None"#));
+    assert_eq!(run!(print, [], vec!["print", "a.txt", "--color", "--no-color"]), ok(r#"Some("a.txt") false false Some(false) None"#));
+    assert_eq!(run!(print, [], vec!["print", "a.txt", "-p", "--no-pager"]), ok(r#"Some("a.txt") false false None Some(false)"#));
+
+    std::env::set_var("PRINT_PAGER", "false");
+    assert_eq!(run!(print, [], vec!["print", "a.txt"]), ok(r#"Some("a.txt") false false None Some(false)"#));
+    assert_eq!(run!(print, [], vec!["print", "a.txt", "-p"]), ok(r#"Some("a.txt") false true None Some(true)"#));
+    std::env::set_var("PRINT_PAGER", "1");
+    assert_eq!(run!(print, [], vec!["print", "a.txt", "--no-pager"]), ok(r#"Some("a.txt") false false None Some(false)"#));
+    std::env::remove_var("PRINT_PAGER");
+
+    // only flags defined as `--[no-]long` are negatable
+    assert_eq!(run!(print, [], vec!["print", "--no-colour"]), (1, vec![]));
+}
@@ -0,0 +1,16 @@
+use commander_rust::{ sub_command, command, execute };
+
+#[sub_command(neg <a>, "negate a number")]
+fn neg(a: i32) -> i32 {
+    -a
+}
+
+#[command(strict, "negative numbers are options", negative_numbers = options)]
+fn strict() {}
+
+#[test]
+fn negative_numbers_are_options() {
+    assert_eq!(execute!(strict, [neg], vec!["strict", "neg", "2"]), -2);
+    // `-2` is regarded as an unknown option, so the exit code is `1` instead of `2`
+    assert_eq!(execute!(strict, [neg], vec!["strict", "neg", "-2"]), 1);
+}
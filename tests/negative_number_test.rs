@@ -0,0 +1,27 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, sub_command, command, FromApp };
+use common::{ record, ok };
+
+#[derive(FromApp)]
+struct AddOpts {
+    offset: Option<i32>,
+}
+
+#[option(--offset <offset>, "offset of the result")]
+#[sub_command(add <a> <b>, "add two numbers")]
+fn add(a: i32, b: i32, opts: AddOpts) {
+    record(format!("{}", a + b + opts.offset.unwrap_or(0)));
+}
+
+#[command(calc, "calculator")]
+fn calc() {}
+
+#[test]
+fn negative_numbers() {
+    assert_eq!(run!(calc, [add], vec!["calc", "add", "-5", "3"]), ok("-2"));
+    assert_eq!(run!(calc, [add], vec!["calc", "add", "-1", "-2"]), ok("-3"));
+    assert_eq!(run!(calc, [add], vec!["calc", "add", "1", "2", "--offset", "-10"]), ok("-7"));
+    assert_eq!(run!(calc, [add], vec!["calc", "add", "1", "2", "--offset=-10"]), ok("-7"));
+}
@@ -0,0 +1,46 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ sub_command, command, default_options, Application };
+use common::{ record, ok };
+
+#[default_options]
+#[sub_command(remote, "manage tracked repositories")]
+fn remote() {
+    record("remote");
+}
+
+#[default_options]
+#[sub_command(add <name> <url>, "add a remote", parent = remote)]
+fn remote_add(name: String, url: String, app: &Application) {
+    assert_eq!(app.sub_name, Some(String::from("add")));
+    assert_eq!(app.sub_path(), "remote add");
+    record(format!("remote add {} {}", name, url));
+}
+
+// nested sub-commands with same name are allowed if their parents are different
+#[sub_command(add <pathspec>, "add file contents to the index")]
+fn add(pathspec: String) {
+    record(format!("add {}", pathspec));
+}
+
+#[sub_command(show, parent = remote_add)]
+fn remote_add_show() {
+    record("remote add show");
+}
+
+#[default_options]
+#[command(git, "the stupid content tracker")]
+fn git() {}
+
+#[test]
+fn dispatch_to_leaf() {
+    let run = |argv: Vec<&str>| run!(git, [remote, remote_add, add, remote_add_show], argv);
+
+    assert_eq!(run(vec!["git", "remote", "add", "origin", "url"]), ok("remote add origin url"));
+    assert_eq!(run(vec!["git", "remote"]), ok("remote"));
+    assert_eq!(run(vec!["git", "add", "src"]), ok("add src"));
+    assert_eq!(run(vec!["git", "remote", "add", "show"]), ok("remote add show"));
+    assert_eq!(run(vec!["git", "remote", "add", "--help"]), (0, vec![]));
+    assert_eq!(run(vec!["git", "remote", "add", "origin"]), (1, vec![]));
+}
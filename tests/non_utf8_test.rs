@@ -0,0 +1,54 @@
+#![cfg(unix)]
+
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, sub_command, command, FromApp };
+use std::ffi::OsString;
+use std::os::unix::ffi::OsStringExt;
+use std::path::PathBuf;
+use common::{ record, ok };
+
+#[derive(FromApp)]
+struct CpOpts {
+    suffix: Option<OsString>,
+}
+
+#[option(-s, --suffix <suffix>, "suffix of backups")]
+#[sub_command(cp <src> <dest>, "copy files")]
+fn cp(src: PathBuf, dest: OsString, opts: CpOpts) {
+    record(format!("{:?} {:?} {:?}", src, dest, opts.suffix));
+}
+
+#[sub_command(echo <text>, "print the text")]
+fn echo(text: String) {
+    record(text);
+}
+
+#[command(fs, "file utils")]
+fn fs() {}
+
+fn os(arg: &str) -> OsString {
+    OsString::from(arg)
+}
+
+#[test]
+fn non_utf8_arguments() {
+    let bad = || OsString::from_vec(vec![b'a', 0xff, b'.', b't', b'x', b't']);
+    let mut suffix = b"--suffix=~".to_vec();
+
+    suffix.push(0xfe);
+
+    // paths and `OsString` receive the bytes as they are
+    assert_eq!(
+        run!(fs, [cp, echo], vec![os("fs"), os("cp"), bad(), os("b.txt")]),
+        ok(r#""a\xFF.txt" "b.txt" None"#),
+    );
+    assert_eq!(
+        run!(fs, [cp, echo], vec![os("fs"), os("cp"), os("a.txt"), bad(), OsString::from_vec(suffix)]),
+        ok(r#""a.txt" "a\xFF.txt" Some("~\xFE")"#),
+    );
+    // `String` can't hold them, it's an error rather than a panic
+    assert_eq!(run!(fs, [cp, echo], vec![os("fs"), os("echo"), os("hi")]), ok("hi"));
+    assert_eq!(run!(fs, [cp, echo], vec![os("fs"), os("echo"), bad()]), (1, vec![]));
+}
@@ -0,0 +1,51 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, sub_command, command, Opts };
+use common::{ record, ok };
+
+#[option(--email <addr>, "send by email", at_least_one_of = "channel")]
+#[option(--sms <phone>, "send by sms", at_least_one_of = "channel")]
+#[option(-u, --urgent, "mark as urgent", requires = ["sms"])]
+#[option(-q, --quiet, "don't ring", conflicts_with = ["urgent", "message"])]
+#[sub_command(send [message], "send a notification")]
+fn send(message: Option<String>, opts: Opts) {
+    let mut channels: Vec<&String> = opts.keys().filter(|key| *key == "email" || *key == "sms").collect();
+
+    channels.sort();
+    record(format!("{} {:?}", message.unwrap_or_default(), channels));
+}
+
+#[option(--json, "output json", exactly_one_of = "format")]
+#[option(--plain, "output plain text", exactly_one_of = "format")]
+#[option(-t, --token <token>, "api token", required_unless = "dry-run")]
+#[option(--dry-run, "don't send anything")]
+#[command(notify, "notification client")]
+fn notify() {}
+
+#[test]
+fn option_constraints() {
+    let run = |argv: Vec<&str>| run!(notify, [send], argv);
+
+    assert_eq!(run(vec!["notify", "send", "hi", "--sms", "110", "-u", "-t", "a", "--json"]), ok(r#"hi ["sms"]"#));
+    assert_eq!(
+        run(vec!["notify", "send", "--email", "a@b.c", "-q", "--sms", "110", "--dry-run", "--plain"]),
+        ok(r#" ["email", "sms"]"#),
+    );
+
+    // none of the group `channel` is offered
+    assert_eq!(run(vec!["notify", "send", "hi", "--dry-run", "--json"]), (1, vec![]));
+    // both of the group `format` are offered
+    assert_eq!(run(vec!["notify", "send", "hi", "--sms", "110", "--dry-run", "--json", "--plain"]), (1, vec![]));
+    // none of the group `format` is offered
+    assert_eq!(run(vec!["notify", "send", "hi", "--sms", "110", "--dry-run"]), (1, vec![]));
+    // `--urgent` requires `--sms`
+    assert_eq!(run(vec!["notify", "send", "hi", "--email", "a@b.c", "-u", "--dry-run", "--json"]), (1, vec![]));
+    // `--quiet` conflicts with the option `--urgent` and the argument `message`
+    assert_eq!(run(vec!["notify", "send", "--sms", "110", "-u", "-q", "--dry-run", "--json"]), (1, vec![]));
+    assert_eq!(run(vec!["notify", "send", "hi", "--sms", "110", "-q", "--dry-run", "--json"]), (1, vec![]));
+    // `--token` is required unless `--dry-run` is offered
+    assert_eq!(run(vec!["notify", "send", "hi", "--sms", "110", "--json"]), (1, vec![]));
+    assert_eq!(run(vec!["notify", "--json"]), (1, vec![]));
+    assert_eq!(run(vec!["notify", "--json", "--token", "a"]), (0, vec![]));
+}
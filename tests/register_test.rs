@@ -1,5 +1,3 @@
-#![feature(proc_macro_hygiene)]
-
 use commander_rust::{ option, sub_command, command };
 use commander_rust::{ Options, SubCommand, };
 use commander_rust::traits::{ PushOptions };
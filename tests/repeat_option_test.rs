@@ -0,0 +1,66 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, command, FromApp, Application, Mixed };
+use common::{ record, ok };
+
+#[derive(FromApp)]
+struct BuildOpts {
+    include: Vec<String>,
+    define: Vec<String>,
+    target: Option<String>,
+    output: Option<String>,
+}
+
+fn fmt_mixed(mixed: Option<&Mixed>) -> String {
+    match mixed {
+        Some(Mixed::Single(arg)) => arg.to_string(),
+        Some(Mixed::Multiply(args)) => args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>().join(","),
+        None => String::new(),
+    }
+}
+
+#[option(-I, --include <dir>, "directories to include", repeat = append)]
+#[option(-D, --define <key> <value>, "define macros", repeat = append)]
+#[option(-t, --target <target>, "target to build", repeat = first)]
+#[option(-o, --output <file>, "output file", repeat = error)]
+#[option(--opt-level <level>, "level of optimization")]
+#[command(cc [file], "compile files")]
+fn cc(file: Option<String>, opts: BuildOpts, app: &Application) {
+    let occurrences: Vec<String> = app
+        .get_opt_occurrences("opt-level")
+        .iter()
+        .map(|args| args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>().join(" "))
+        .collect();
+    let level = app.global_opts.get("opt-level").map(|args| fmt_mixed(args.get("level")));
+
+    record(format!(
+        "{:?} {:?} {:?} {:?} {:?} {:?} {:?}",
+        file, opts.include, opts.define, opts.target, opts.output, level, occurrences,
+    ));
+}
+
+#[test]
+fn repeat_policies() {
+    assert_eq!(run!(cc, [], vec!["cc", "main.c"]), ok(r#"Some("main.c") [] [] None None None []"#));
+    assert_eq!(
+        run!(cc, [], vec!["cc", "main.c", "-I", "a", "--include", "b", "-I", "c"]),
+        ok(r#"Some("main.c") ["a", "b", "c"] [] None None None []"#),
+    );
+    assert_eq!(
+        run!(cc, [], vec!["cc", "main.c", "-D", "DEBUG", "1", "-D", "LOG", "0"]),
+        ok(r#"Some("main.c") [] ["DEBUG", "1", "LOG", "0"] None None None []"#),
+    );
+    assert_eq!(
+        run!(cc, [], vec!["cc", "main.c", "-t", "x86", "--target", "arm"]),
+        ok(r#"Some("main.c") [] [] Some("x86") None None []"#),
+    );
+    // the last occurrence wins by default, all occurrences are still available
+    assert_eq!(
+        run!(cc, [], vec!["cc", "main.c", "--opt-level", "1", "--opt-level", "3"]),
+        ok(r#"Some("main.c") [] [] None None Some("3") ["1", "3"]"#),
+    );
+    assert_eq!(run!(cc, [], vec!["cc", "main.c", "-o", "main"]), ok(r#"Some("main.c") [] [] None Some("main") None []"#));
+    // `-o` can only be offered once
+    assert_eq!(run!(cc, [], vec!["cc", "main.c", "-o", "main", "-o", "app"]), (1, vec![]));
+}
@@ -0,0 +1,44 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, sub_command, command, FromApp };
+use common::{ record, ok };
+
+#[derive(FromApp)]
+struct DeployOpts {
+    token: String,
+    region: String,
+}
+
+#[option(-r, --region <region>, "region to deploy", required)]
+#[sub_command(deploy <app>, "deploy an app")]
+fn deploy(app: String, opts: DeployOpts) {
+    record(format!("{} {} {}", app, opts.token, opts.region));
+}
+
+#[sub_command(status, "show status")]
+fn status() {}
+
+#[option(-t, --token <token>, "api token", env = "CLOUD_TOKEN", required)]
+#[command(cloud, "cloud client")]
+fn cloud() {}
+
+#[test]
+fn required_options() {
+    let run = |argv: Vec<&str>| run!(cloud, [deploy, status], argv);
+
+    assert_eq!(run(vec!["cloud", "deploy", "web", "-r", "eu", "-t", "secret"]), ok("web secret eu"));
+
+    // local required option is missing
+    assert_eq!(run(vec!["cloud", "deploy", "web", "-t", "secret"]), (1, vec![]));
+    // global required option is missing
+    assert_eq!(run(vec!["cloud", "deploy", "web", "-r", "eu"]), (1, vec![]));
+    assert_eq!(run(vec!["cloud", "status"]), (1, vec![]));
+    assert_eq!(run(vec!["cloud"]), (1, vec![]));
+    assert_eq!(run(vec!["cloud", "status", "--token", "secret"]), (0, vec![]));
+
+    // environment variables are accepted
+    std::env::set_var("CLOUD_TOKEN", "env-secret");
+    assert_eq!(run(vec!["cloud", "deploy", "api", "--region", "us"]), ok("api env-secret us"));
+    std::env::remove_var("CLOUD_TOKEN");
+}
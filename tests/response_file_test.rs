@@ -0,0 +1,39 @@
+#[macro_use]
+mod common;
+
+use commander_rust::{ option, command, GlobalOpts, Mixed };
+use common::{ record, ok };
+
+#[option(-o, --output <file>, "output file")]
+#[option(-I, --include <dir>, "directories to include", repeat = append)]
+#[command(cc <..files>, "compiler", response_files)]
+fn cc(files: Vec<String>, opts: GlobalOpts) {
+    let include = match opts.get("include").and_then(|args| args.get("dir")) {
+        Some(Mixed::Multiply(dirs)) => dirs.to_string(),
+        Some(Mixed::Single(dir)) => dir.to_string(),
+        None => String::new(),
+    };
+
+    record(format!("{} | {} | {}", files.join(","), opts.contains_key("output"), include));
+}
+
+#[test]
+fn response_files() {
+    let path = std::env::temp_dir().join(format!("commander-rust-{}-cc.rsp", std::process::id()));
+    let token = format!("@{}", path.display());
+
+    std::fs::write(&path, "# sources\na.c \"b c.c\"\n-I 'include dir'\n-I lib\n").unwrap();
+
+    assert_eq!(
+        run!(cc, [], vec!["cc", "main.c", &token, "-o", "out"]),
+        ok("main.c,a.c,b c.c | true | include dir lib"),
+    );
+    // `--` stops expansion
+    assert_eq!(run!(cc, [], vec!["cc", "--", &token]), ok(&format!("{} | false | ", token)));
+
+    std::fs::write(&path, "a.c\n'b.c").unwrap();
+    assert_eq!(run!(cc, [], vec!["cc", &token]), (1, vec![]));
+
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(run!(cc, [], vec!["cc", &token]), (1, vec![]));
+}
@@ -0,0 +1,45 @@
+use commander_rust::{ sub_command, command, option, execute, Opts };
+use std::sync::atomic::{ AtomicUsize, Ordering };
+
+static INSTALLED: AtomicUsize = AtomicUsize::new(0);
+static REMOVED: AtomicUsize = AtomicUsize::new(0);
+static FORCED: AtomicUsize = AtomicUsize::new(0);
+
+#[option(-f, --force, "install forcibly")]
+#[sub_command(install|i|add <pkg>, "install a package")]
+fn install(pkg: String, opts: Opts) -> i32 {
+    assert_eq!(pkg, "react");
+    INSTALLED.fetch_add(1, Ordering::SeqCst);
+
+    if opts.contains_key("force") {
+        FORCED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    0
+}
+
+#[sub_command(uninstall|rm <pkg>, "uninstall a package")]
+fn uninstall(pkg: String) -> i32 {
+    assert_eq!(pkg, "react");
+    REMOVED.fetch_add(1, Ordering::SeqCst);
+    0
+}
+
+#[command(npms, "node package manager")]
+fn npms() {}
+
+#[test]
+fn aliases() {
+    assert_eq!(execute!(npms, [install, uninstall], vec!["npms", "install", "react"]), 0);
+    assert_eq!(execute!(npms, [install, uninstall], vec!["npms", "i", "react"]), 0);
+    assert_eq!(execute!(npms, [install, uninstall], vec!["npms", "add", "react", "-f"]), 0);
+    assert_eq!(INSTALLED.load(Ordering::SeqCst), 3);
+    assert_eq!(FORCED.load(Ordering::SeqCst), 1);
+
+    assert_eq!(execute!(npms, [install, uninstall], vec!["npms", "rm", "react"]), 0);
+    assert_eq!(REMOVED.load(Ordering::SeqCst), 1);
+
+    // arguments are checked as usual
+    assert_eq!(execute!(npms, [install, uninstall], vec!["npms", "i"]), 1);
+    assert_eq!(INSTALLED.load(Ordering::SeqCst), 3);
+}